@@ -0,0 +1,507 @@
+#![warn(clippy::all)]
+use std::{env::args, path::PathBuf, str::FromStr};
+
+use crate::{
+    error::{Error, ErrorKind, Result},
+    graph::{
+        dot::DotOptions,
+        projection::{Normalization, SharedBy},
+        snapshots::Window,
+    },
+    query_structs::{tables::TableFormat, write_csv::OrganizationLayout},
+};
+
+/// Formats that the scraped nodes may be written in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// A single pretty printed JSON array per repository.
+    Json,
+    /// One compact JSON object per line (also known as NDJSON).
+    JsonLines,
+    /// Flat CSV rows for R, pandas, and friends.
+    Csv,
+    /// The contributor graph as GraphML.
+    GraphMl,
+    /// The contributor graph as dynamic GEXF with time stamped edges.
+    Gexf,
+    /// The contributor graph in Graphviz's DOT language.
+    Dot,
+    /// Normalized users, repositories, pull requests, participations, and user organizations CSV
+    /// tables.
+    Tables,
+    /// The normalized tables merged into a single SQLite database.
+    Sqlite,
+    /// Columnar Parquet with typed timestamps and organizations as a list column.
+    Parquet,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::JsonLines => "jsonl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::GraphMl => "graphml",
+            OutputFormat::Gexf => "gexf",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Tables => "csv",
+            OutputFormat::Sqlite => "sqlite",
+            OutputFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "jsonl" | "ndjson" => Ok(OutputFormat::JsonLines),
+            "csv" => Ok(OutputFormat::Csv),
+            "graphml" => Ok(OutputFormat::GraphMl),
+            "gexf" => Ok(OutputFormat::Gexf),
+            "dot" => Ok(OutputFormat::Dot),
+            "tables" => Ok(OutputFormat::Tables),
+            "sqlite" => Ok(OutputFormat::Sqlite),
+            "parquet" => Ok(OutputFormat::Parquet),
+            _ => Err(Error::new(
+                format!("Unknown output format ({})", format),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+// Figure out lifetimes later instead of creating Strings
+pub struct RepositoryArg {
+    pub owner: String,
+    pub name: String,
+}
+
+impl FromStr for RepositoryArg {
+    type Err = Error;
+
+    fn from_str(arg: &str) -> Result<Self> {
+        let mut repo = arg.split('/');
+        Ok(RepositoryArg {
+            owner: repo
+                .next()
+                .ok_or_else(|| {
+                    Error::new(format!("Parsing repository ({})", arg), ErrorKind::BadArgs)
+                })?
+                .to_owned(),
+            name: repo
+                .next()
+                .ok_or_else(|| {
+                    Error::new(format!("Parsing repository ({})", arg), ErrorKind::BadArgs)
+                })?
+                .to_owned(),
+        })
+    }
+}
+
+/// Options for a scraping run.
+pub struct Args {
+    pub repositories: Vec<RepositoryArg>,
+    pub format: OutputFormat,
+    /// Append to existing output files rather than overwriting them. Only JSON Lines output may
+    /// be appended to since a JSON array can't simply be extended.
+    pub append: bool,
+    /// Layout of the organizations column for CSV output.
+    pub org_layout: OrganizationLayout,
+    /// View, weight filter, and clustering for DOT output.
+    pub dot: DotOptions,
+    /// Database that SQLite output is merged into.
+    pub database: PathBuf,
+    /// File format of each table for tables output.
+    pub table_format: TableFormat,
+    /// Also scrape issues and their participants.
+    pub issues: bool,
+    /// Also write pull request reviews and review comments.
+    pub reviews: bool,
+    /// Also scrape the default branch's commit history.
+    pub commits: bool,
+    /// Also scrape stargazers, watchers, and forks.
+    pub adoption: bool,
+    /// Also scrape discussions, their comments, and replies.
+    pub discussions: bool,
+    /// Estimate the scrape's cost instead of running it.
+    pub dry_run: bool,
+}
+
+/// Options for the coparticipation and reposimilarity subcommands.
+pub struct ProjectionArgs {
+    /// Node files previously written by a scraping run.
+    pub inputs: Vec<PathBuf>,
+    /// Defaults to output/{subcommand}.{extension} if not set.
+    pub output: Option<PathBuf>,
+    /// Csv writes a weighted edge list; the graph formats write the projected graph.
+    pub format: OutputFormat,
+    /// Only used by coparticipation.
+    pub shared_by: SharedBy,
+    pub normalization: Normalization,
+    pub dot: DotOptions,
+}
+
+/// Options for the metrics subcommand.
+pub struct MetricsArgs {
+    pub inputs: Vec<PathBuf>,
+    /// Per user report. Defaults to output/metrics.{extension}.
+    pub output: Option<PathBuf>,
+    /// Csv or Json.
+    pub format: OutputFormat,
+    pub summary: PathBuf,
+}
+
+/// Options for the communities subcommand.
+pub struct CommunityArgs {
+    pub inputs: Vec<PathBuf>,
+    /// Defaults to output/communities.{extension}.
+    pub output: Option<PathBuf>,
+    /// Csv and Json list each user's community; the graph formats write the co-participation
+    /// graph with a community attribute.
+    pub format: OutputFormat,
+    /// Modularity scores and the comparison against companies and organizations.
+    pub report: PathBuf,
+    pub dot: DotOptions,
+}
+
+/// Options for the snapshots subcommand.
+pub struct SnapshotArgs {
+    pub inputs: Vec<PathBuf>,
+    pub window: Window,
+    /// One graph per window is written here as {window}.{extension}.
+    pub output_dir: PathBuf,
+    /// GraphMl, Gexf, or Dot.
+    pub format: OutputFormat,
+    /// Per window activity CSV.
+    pub metrics: PathBuf,
+    pub dot: DotOptions,
+}
+
+/// The program either scrapes repositories or analyzes previously scraped nodes.
+pub enum Command {
+    Scrape(Args),
+    CoParticipation(ProjectionArgs),
+    RepositorySimilarity(ProjectionArgs),
+    Metrics(MetricsArgs),
+    Communities(CommunityArgs),
+    Snapshots(SnapshotArgs),
+}
+
+// Pulls the value following a flag or reports the flag as missing a value.
+fn flag_value<I>(flag: &str, args: &mut I) -> Result<String>
+where
+    I: Iterator<Item = String>,
+{
+    args.next().ok_or_else(|| {
+        Error::new(
+            format!("The flag {} requires a value", flag),
+            ErrorKind::BadArgs,
+        )
+    })
+}
+
+// Parses a flag's numeric value.
+fn parse_number<T>(flag: &str, value: String) -> Result<T>
+where
+    T: FromStr,
+{
+    value.parse().map_err(|_| {
+        Error::new(
+            format!("{} requires a number but got {}", flag, value),
+            ErrorKind::BadArgs,
+        )
+    })
+}
+
+// Parses command line arguments. The first argument may name a subcommand; scraping is the
+// default.
+pub fn parse_args() -> Result<Command> {
+    let mut args = args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("coparticipation") => {
+            args.next();
+            Ok(Command::CoParticipation(parse_projection_args(args)?))
+        }
+        Some("reposimilarity") => {
+            args.next();
+            Ok(Command::RepositorySimilarity(parse_projection_args(args)?))
+        }
+        Some("metrics") => {
+            args.next();
+            Ok(Command::Metrics(parse_metrics_args(args)?))
+        }
+        Some("communities") => {
+            args.next();
+            Ok(Command::Communities(parse_community_args(args)?))
+        }
+        Some("snapshots") => {
+            args.next();
+            Ok(Command::Snapshots(parse_snapshot_args(args)?))
+        }
+        _ => Ok(Command::Scrape(parse_scrape_args(args)?)),
+    }
+}
+
+// Flags may appear anywhere; everything else is a repository name.
+fn parse_scrape_args<I>(mut args: I) -> Result<Args>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = Args {
+        repositories: Vec::new(),
+        format: OutputFormat::Json,
+        append: false,
+        org_layout: OrganizationLayout::Delimited,
+        dot: DotOptions::default(),
+        database: PathBuf::from("output/github.sqlite"),
+        table_format: TableFormat::Csv,
+        issues: false,
+        reviews: false,
+        commits: false,
+        adoption: false,
+        discussions: false,
+        dry_run: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => parsed.format = flag_value(&arg, &mut args)?.parse()?,
+            "--append" => parsed.append = true,
+            "--orgs" => parsed.org_layout = flag_value(&arg, &mut args)?.parse()?,
+            "--dot-view" => parsed.dot.view = flag_value(&arg, &mut args)?.parse()?,
+            "--min-weight" => {
+                parsed.dot.min_weight = parse_number(&arg, flag_value(&arg, &mut args)?)?
+            }
+            "--cluster" => parsed.dot.clustering = flag_value(&arg, &mut args)?.parse()?,
+            "--database" => parsed.database = flag_value(&arg, &mut args)?.into(),
+            "--table-format" => parsed.table_format = flag_value(&arg, &mut args)?.parse()?,
+            "--issues" => parsed.issues = true,
+            "--reviews" => parsed.reviews = true,
+            "--commits" => parsed.commits = true,
+            "--adoption" => parsed.adoption = true,
+            "--discussions" => parsed.discussions = true,
+            "--dry-run" => parsed.dry_run = true,
+            flag if flag.starts_with("--") => {
+                return Err(Error::new(
+                    format!("Unknown flag ({})", flag),
+                    ErrorKind::BadArgs,
+                ))
+            }
+            _ => parsed.repositories.push(arg.parse()?),
+        }
+    }
+
+    if parsed.repositories.is_empty() {
+        Err(Error::new("No repositories found", ErrorKind::BadArgs))
+    } else if parsed.append && parsed.format != OutputFormat::JsonLines {
+        Err(Error::new(
+            "--append requires --format jsonl",
+            ErrorKind::BadArgs,
+        ))
+    } else if (parsed.issues
+        || parsed.reviews
+        || parsed.commits
+        || parsed.adoption
+        || parsed.discussions)
+        && matches!(
+            parsed.format,
+            OutputFormat::Tables | OutputFormat::Sqlite | OutputFormat::Parquet
+        )
+    {
+        Err(Error::new(
+            "--issues, --reviews, --commits, --adoption, and --discussions require json, jsonl, \
+             csv, graphml, gexf, or dot",
+            ErrorKind::BadArgs,
+        ))
+    } else {
+        Ok(parsed)
+    }
+}
+
+// Flags may appear anywhere; everything else is an input file.
+fn parse_projection_args<I>(mut args: I) -> Result<ProjectionArgs>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = ProjectionArgs {
+        inputs: Vec::new(),
+        output: None,
+        format: OutputFormat::Csv,
+        shared_by: SharedBy::PullRequests,
+        normalization: Normalization::None,
+        dot: DotOptions::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => parsed.output = Some(flag_value(&arg, &mut args)?.into()),
+            "--format" => parsed.format = flag_value(&arg, &mut args)?.parse()?,
+            "--by" => parsed.shared_by = flag_value(&arg, &mut args)?.parse()?,
+            "--normalize" => parsed.normalization = flag_value(&arg, &mut args)?.parse()?,
+            "--min-weight" => {
+                parsed.dot.min_weight = parse_number(&arg, flag_value(&arg, &mut args)?)?
+            }
+            "--cluster" => parsed.dot.clustering = flag_value(&arg, &mut args)?.parse()?,
+            flag if flag.starts_with("--") => {
+                return Err(Error::new(
+                    format!("Unknown flag ({})", flag),
+                    ErrorKind::BadArgs,
+                ))
+            }
+            _ => parsed.inputs.push(arg.into()),
+        }
+    }
+
+    if parsed.inputs.is_empty() {
+        Err(Error::new("No input files found", ErrorKind::BadArgs))
+    } else if let OutputFormat::Json
+    | OutputFormat::JsonLines
+    | OutputFormat::Tables
+    | OutputFormat::Sqlite
+    | OutputFormat::Parquet = parsed.format
+    {
+        Err(Error::new(
+            "Projections are written as csv, graphml, gexf, or dot",
+            ErrorKind::BadArgs,
+        ))
+    } else {
+        Ok(parsed)
+    }
+}
+
+// Flags may appear anywhere; everything else is an input file.
+fn parse_metrics_args<I>(mut args: I) -> Result<MetricsArgs>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = MetricsArgs {
+        inputs: Vec::new(),
+        output: None,
+        format: OutputFormat::Csv,
+        summary: PathBuf::from("output/metrics_summary.json"),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => parsed.output = Some(flag_value(&arg, &mut args)?.into()),
+            "--format" => parsed.format = flag_value(&arg, &mut args)?.parse()?,
+            "--summary" => parsed.summary = flag_value(&arg, &mut args)?.into(),
+            flag if flag.starts_with("--") => {
+                return Err(Error::new(
+                    format!("Unknown flag ({})", flag),
+                    ErrorKind::BadArgs,
+                ))
+            }
+            _ => parsed.inputs.push(arg.into()),
+        }
+    }
+
+    if parsed.inputs.is_empty() {
+        Err(Error::new("No input files found", ErrorKind::BadArgs))
+    } else if let OutputFormat::Csv | OutputFormat::Json = parsed.format {
+        Ok(parsed)
+    } else {
+        Err(Error::new(
+            "Metrics are written as csv or json",
+            ErrorKind::BadArgs,
+        ))
+    }
+}
+
+// Flags may appear anywhere; everything else is an input file.
+fn parse_community_args<I>(mut args: I) -> Result<CommunityArgs>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = CommunityArgs {
+        inputs: Vec::new(),
+        output: None,
+        format: OutputFormat::Csv,
+        report: PathBuf::from("output/communities_report.json"),
+        dot: DotOptions::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => parsed.output = Some(flag_value(&arg, &mut args)?.into()),
+            "--format" => parsed.format = flag_value(&arg, &mut args)?.parse()?,
+            "--report" => parsed.report = flag_value(&arg, &mut args)?.into(),
+            "--min-weight" => {
+                parsed.dot.min_weight = parse_number(&arg, flag_value(&arg, &mut args)?)?
+            }
+            "--cluster" => parsed.dot.clustering = flag_value(&arg, &mut args)?.parse()?,
+            flag if flag.starts_with("--") => {
+                return Err(Error::new(
+                    format!("Unknown flag ({})", flag),
+                    ErrorKind::BadArgs,
+                ))
+            }
+            _ => parsed.inputs.push(arg.into()),
+        }
+    }
+
+    if parsed.inputs.is_empty() {
+        Err(Error::new("No input files found", ErrorKind::BadArgs))
+    } else if let OutputFormat::JsonLines
+    | OutputFormat::Tables
+    | OutputFormat::Sqlite
+    | OutputFormat::Parquet = parsed.format
+    {
+        Err(Error::new(
+            "Communities are written as csv, json, graphml, gexf, or dot",
+            ErrorKind::BadArgs,
+        ))
+    } else {
+        Ok(parsed)
+    }
+}
+
+// Flags may appear anywhere; everything else is an input file.
+fn parse_snapshot_args<I>(mut args: I) -> Result<SnapshotArgs>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = SnapshotArgs {
+        inputs: Vec::new(),
+        window: Window::Monthly,
+        output_dir: PathBuf::from("output/snapshots"),
+        format: OutputFormat::GraphMl,
+        metrics: PathBuf::from("output/snapshots/metrics.csv"),
+        dot: DotOptions::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--window" => parsed.window = flag_value(&arg, &mut args)?.parse()?,
+            "--output-dir" => parsed.output_dir = flag_value(&arg, &mut args)?.into(),
+            "--format" => parsed.format = flag_value(&arg, &mut args)?.parse()?,
+            "--metrics" => parsed.metrics = flag_value(&arg, &mut args)?.into(),
+            "--min-weight" => {
+                parsed.dot.min_weight = parse_number(&arg, flag_value(&arg, &mut args)?)?
+            }
+            "--cluster" => parsed.dot.clustering = flag_value(&arg, &mut args)?.parse()?,
+            "--dot-view" => parsed.dot.view = flag_value(&arg, &mut args)?.parse()?,
+            flag if flag.starts_with("--") => {
+                return Err(Error::new(
+                    format!("Unknown flag ({})", flag),
+                    ErrorKind::BadArgs,
+                ))
+            }
+            _ => parsed.inputs.push(arg.into()),
+        }
+    }
+
+    if parsed.inputs.is_empty() {
+        Err(Error::new("No input files found", ErrorKind::BadArgs))
+    } else if let OutputFormat::GraphMl | OutputFormat::Gexf | OutputFormat::Dot = parsed.format {
+        Ok(parsed)
+    } else {
+        Err(Error::new(
+            "Snapshots are written as graphml, gexf, or dot",
+            ErrorKind::BadArgs,
+        ))
+    }
+}
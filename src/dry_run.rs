@@ -0,0 +1,197 @@
+#![warn(clippy::all)]
+use crate::{
+    args::Args,
+    error::Result,
+    query_client::DEFAULT_TIMEOUT,
+    query_structs::{
+        adoptionview::{fork_view, stargazer_view, watcher_view},
+        batchview::{batch_size, repository_cost},
+        commitview::commit_view,
+        discussionview::discussion_view,
+        issueview::issue_view,
+        profileview::{profile_view, MAX_IDS},
+        query_cost::QueryCost,
+        ratelimitview::rate_limit_view::RateLimitViewRateLimit,
+        repoview::repo_view,
+        reviewview::review_view,
+        scalars::DateTime,
+    },
+};
+use chrono::offset::Utc;
+
+// GitHub's hourly budget for personal access tokens, used if the real one can't be fetched.
+const DEFAULT_LIMIT: i64 = 5000;
+const SECONDS_PER_HOUR: i64 = 3600;
+
+/// The first page of one query for every repository it runs on.
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    pub query: &'static str,
+    pub requests: i64,
+    /// Worst case cost of each request.
+    pub cost: QueryCost,
+}
+
+impl Estimate {
+    pub fn points(&self) -> i64 {
+        self.requests * self.cost.points()
+    }
+}
+
+/// The token's rate limit at the time of the dry run.
+#[derive(Clone, Debug)]
+pub struct Budget {
+    pub limit: i64,
+    pub remaining: i64,
+    pub reset_at: Option<DateTime>,
+}
+
+impl Budget {
+    /// A fresh hour of GitHub's default limit.
+    pub fn assumed() -> Self {
+        Budget {
+            limit: DEFAULT_LIMIT,
+            remaining: DEFAULT_LIMIT,
+            reset_at: None,
+        }
+    }
+}
+
+impl From<RateLimitViewRateLimit> for Budget {
+    fn from(rate_limit: RateLimitViewRateLimit) -> Self {
+        Budget {
+            limit: rate_limit.limit,
+            remaining: rate_limit.remaining,
+            reset_at: Some(rate_limit.reset_at),
+        }
+    }
+}
+
+/// Estimates the first page of every query a scrape with args would make. Nothing says how many
+/// pages a repository has without fetching it, but every later page costs the same as the first.
+pub fn estimate(args: &Args, nnodes: i64) -> Result<Vec<Estimate>> {
+    let repositories = args.repositories.len() as i64;
+    let per_repository = |query, document| Estimate {
+        query,
+        requests: repositories,
+        cost: QueryCost::estimate(document, nnodes),
+    };
+    let mut estimates = Vec::new();
+
+    let per_batch = (batch_size(nnodes)? as i64).min(repositories);
+    if per_batch > 1 {
+        let cost = repository_cost(nnodes)?;
+        estimates.push(Estimate {
+            query: "pull requests",
+            requests: (repositories + per_batch - 1) / per_batch,
+            cost: QueryCost {
+                nodes: cost.nodes * per_batch,
+                requests: cost.requests * per_batch,
+            },
+        });
+    } else {
+        estimates.push(per_repository("pull requests", repo_view::QUERY));
+    }
+    if args.reviews {
+        estimates.push(per_repository("reviews", review_view::QUERY));
+    }
+    if args.issues {
+        estimates.push(per_repository("issues", issue_view::QUERY));
+    }
+    if args.commits {
+        estimates.push(per_repository("commits", commit_view::QUERY));
+    }
+    if args.adoption {
+        estimates.push(per_repository("stargazers", stargazer_view::QUERY));
+        estimates.push(per_repository("watchers", watcher_view::QUERY));
+        estimates.push(per_repository("forks", fork_view::QUERY));
+    }
+    if args.discussions {
+        estimates.push(per_repository("discussions", discussion_view::QUERY));
+    }
+
+    // Worst case every participant on the first page of pull requests (and issues) is someone
+    // new. Each request looks up to 100 of them, which the estimate can't see since nodes(ids:)
+    // isn't a connection.
+    let pages = if args.issues { 2 } else { 1 };
+    let participants = (repositories * pages * nnodes * nnodes).max(1);
+    let max_ids = MAX_IDS as i64;
+    let users = participants.min(max_ids);
+    let per_user = QueryCost::estimate(profile_view::QUERY, nnodes);
+    estimates.push(Estimate {
+        query: "profiles",
+        requests: (participants + max_ids - 1) / max_ids,
+        cost: QueryCost {
+            nodes: users * (per_user.nodes + 1),
+            requests: users * per_user.requests,
+        },
+    });
+    Ok(estimates)
+}
+
+/// Seconds the requests take: the pause after each one plus waiting for the rate limit to reset
+/// whenever the budget runs out.
+pub fn wall_clock(estimates: &[Estimate], budget: &Budget) -> i64 {
+    let requests: i64 = estimates.iter().map(|estimate| estimate.requests).sum();
+    let points: i64 = estimates.iter().map(Estimate::points).sum();
+    let mut seconds = requests * DEFAULT_TIMEOUT as i64;
+
+    if points > budget.remaining {
+        let until_reset = budget.reset_at.map_or(SECONDS_PER_HOUR, |reset_at| {
+            (reset_at.0 - Utc::now()).num_seconds().max(0)
+        });
+        // Every hour after the reset renews the full limit.
+        let hours = (points - budget.remaining - 1) / budget.limit.max(1);
+        seconds += until_reset + hours * SECONDS_PER_HOUR;
+    }
+    seconds
+}
+
+fn format_seconds(seconds: i64) -> String {
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / SECONDS_PER_HOUR,
+        seconds % SECONDS_PER_HOUR / 60,
+        seconds % 60
+    )
+}
+
+/// Prints a table of the estimates followed by the totals and how long they'd take.
+pub fn print_report(estimates: &[Estimate], budget: &Budget) {
+    println!(
+        "{:<14} {:>9} {:>9} {:>14}",
+        "query", "requests", "points", "nodes/request"
+    );
+    for estimate in estimates {
+        println!(
+            "{:<14} {:>9} {:>9} {:>14}",
+            estimate.query,
+            estimate.requests,
+            estimate.points(),
+            estimate.cost.nodes
+        );
+    }
+    let requests: i64 = estimates.iter().map(|estimate| estimate.requests).sum();
+    let points: i64 = estimates.iter().map(Estimate::points).sum();
+    println!("{:<14} {:>9} {:>9}", "total", requests, points);
+
+    match budget.reset_at {
+        Some(reset_at) => println!(
+            "\n{} of {} points remaining until {}",
+            budget.remaining, budget.limit, reset_at
+        ),
+        None => println!(
+            "\nAssuming {} of {} points remaining",
+            budget.remaining, budget.limit
+        ),
+    }
+    println!(
+        "At least {} of wall-clock time",
+        format_seconds(wall_clock(estimates, budget))
+    );
+    println!(
+        "Each further page of a query costs as much as its first and profiles take a request per \
+         {} participants.",
+        MAX_IDS
+    );
+}
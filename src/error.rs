@@ -1,4 +1,6 @@
 pub use super::errorkind::ErrorKind;
+use arrow::error::ArrowError;
+use csv::Error as CsvError;
 use serde_json::Error as JsonError;
 #[warn(clippy::all)]
 use std::{
@@ -6,7 +8,9 @@ use std::{
     io::Error as IoError,
 };
 
+use parquet::errors::ParquetError;
 use reqwest::Error as ReqwestError;
+use rusqlite::Error as SqliteError;
 
 #[derive(Debug)]
 pub struct Error {
@@ -43,6 +47,10 @@ impl Display for Error {
                 token. Context: {}",
                 self.context
             ),
+            ErrorKind::Arrow(arrow) => {
+                write!(f, "Arrow error: {}\nContext: {}", arrow, self.context)
+            }
+            ErrorKind::Csv(csv) => write!(f, "CSV error: {}\nContext: {}", csv, self.context),
             ErrorKind::Io(io) => write!(f, "IO Error: {}\nContext: {}", io, self.context),
             ErrorKind::Json(json) => write!(
                 f,
@@ -50,9 +58,21 @@ impl Display for Error {
                 json,
                 context = self.context()
             ),
+            ErrorKind::Parquet(parquet) => {
+                write!(f, "Parquet error: {}\nContext: {}", parquet, self.context)
+            }
             ErrorKind::Reqwest(reqw) => {
                 write!(f, "Reqwest error: {}\nContext {}", reqw, self.context)
             }
+            ErrorKind::Sqlite(sqlite) => {
+                write!(f, "SQLite error: {}\nContext: {}", sqlite, self.context)
+            }
+            ErrorKind::Timestamp(timestamp) => write!(
+                f,
+                "Timestamp parse error: {}\nContext: {}",
+                timestamp, self.context
+            ),
+            ErrorKind::Url(url) => write!(f, "URL parse error: {}\nContext: {}", url, self.context),
         }
     }
 }
@@ -67,6 +87,12 @@ impl From<JsonError> for Error {
     }
 }
 
+impl From<CsvError> for Error {
+    fn from(csv: CsvError) -> Self {
+        Error::new("Empty context", ErrorKind::Csv(csv))
+    }
+}
+
 impl From<IoError> for Error {
     fn from(io: IoError) -> Self {
         Error::new("Empty context", ErrorKind::Io(io))
@@ -78,3 +104,21 @@ impl From<ReqwestError> for Error {
         Error::new("Empty context", ErrorKind::Reqwest(reqw))
     }
 }
+
+impl From<SqliteError> for Error {
+    fn from(sqlite: SqliteError) -> Self {
+        Error::new("Empty context", ErrorKind::Sqlite(sqlite))
+    }
+}
+
+impl From<ArrowError> for Error {
+    fn from(arrow: ArrowError) -> Self {
+        Error::new("Empty context", ErrorKind::Arrow(arrow))
+    }
+}
+
+impl From<ParquetError> for Error {
+    fn from(parquet: ParquetError) -> Self {
+        Error::new("Empty context", ErrorKind::Parquet(parquet))
+    }
+}
@@ -1,14 +1,26 @@
+use arrow::error::ArrowError;
+use chrono::ParseError as TimestampError;
+use csv::Error as CsvError;
+use parquet::errors::ParquetError;
 use reqwest::Error as ReqwestError;
+use rusqlite::Error as SqliteError;
 #[warn(clippy::all)]
 use serde_json::Error as JsonError;
 use std::io::Error as IoError;
+use url::ParseError as UrlError;
 
 #[derive(Debug)]
 pub enum ErrorKind {
     BadArgs,
     EmptyData,
     NoToken,
+    Arrow(ArrowError),
+    Csv(CsvError),
     Json(JsonError),
     Io(IoError),
+    Parquet(ParquetError),
     Reqwest(ReqwestError),
+    Sqlite(SqliteError),
+    Timestamp(TimestampError),
+    Url(UrlError),
 }
@@ -0,0 +1,69 @@
+#![warn(clippy::all)]
+use super::contributor_graph::ContributorGraph;
+
+/// Undirected, weighted adjacency lists over a ContributorGraph's vertex indices. The network
+/// algorithms work on this rather than the typed graph since they don't care about kinds.
+#[derive(Clone, Debug)]
+pub struct Adjacency {
+    // (neighbor, weight) for each vertex.
+    neighbors: Vec<Vec<(usize, f64)>>,
+    edge_count: usize,
+}
+
+impl Adjacency {
+    /// Treats every edge as undirected with its weight as the edge weight. Self loops are dropped.
+    pub fn from_graph(graph: &ContributorGraph) -> Self {
+        let mut neighbors = vec![Vec::new(); graph.vertices().len()];
+        let mut edge_count = 0;
+        for edge in graph.edges() {
+            if edge.source != edge.target {
+                let weight = edge.weight as f64;
+                neighbors[edge.source].push((edge.target, weight));
+                neighbors[edge.target].push((edge.source, weight));
+                edge_count += 1;
+            }
+        }
+        Adjacency {
+            neighbors,
+            edge_count,
+        }
+    }
+
+    /// Builds adjacency lists from (source, target, weight) triples for hand made fixtures.
+    #[cfg(test)]
+    pub fn from_edges(vertex_count: usize, edges: &[(usize, usize, f64)]) -> Self {
+        let mut neighbors = vec![Vec::new(); vertex_count];
+        for &(source, target, weight) in edges {
+            neighbors[source].push((target, weight));
+            neighbors[target].push((source, weight));
+        }
+        Adjacency {
+            neighbors,
+            edge_count: edges.len(),
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn neighbors(&self, vertex: usize) -> &[(usize, f64)] {
+        &self.neighbors[vertex]
+    }
+
+    pub fn degree(&self, vertex: usize) -> usize {
+        self.neighbors[vertex].len()
+    }
+
+    /// Sum of the weights of a vertex's edges.
+    pub fn strength(&self, vertex: usize) -> f64 {
+        self.neighbors[vertex]
+            .iter()
+            .map(|&(_, weight)| weight)
+            .sum()
+    }
+}
@@ -0,0 +1,383 @@
+#![warn(clippy::all)]
+use super::{
+    adjacency::Adjacency,
+    contributor_graph::{ContributorGraph, EdgeKind, VertexKind},
+    projection::{co_participation, Normalization, SharedBy},
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// Moves must improve modularity by more than this to count; guards against looping on floating
+// point noise.
+const MIN_GAIN: f64 = 1e-12;
+
+/// How a detected community lines up with the company and organization fields.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommunitySummary {
+    pub community: usize,
+    pub size: usize,
+    /// Most common company among the community's members.
+    pub top_company: Option<String>,
+    /// Share of members that list top_company.
+    pub top_company_share: f64,
+    pub top_organization: Option<String>,
+    pub top_organization_share: f64,
+}
+
+/// A user's detected community alongside their company for comparison.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommunityMember {
+    pub user: String,
+    pub community: Option<usize>,
+    pub company: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CommunityReport {
+    /// Modularity of the detected communities.
+    pub modularity: f64,
+    /// Modularity of grouping users by company. Users without a company are left on their own.
+    pub company_modularity: f64,
+    /// Modularity of grouping users by their first organization.
+    pub organization_modularity: f64,
+    /// Largest community first.
+    pub communities: Vec<CommunitySummary>,
+}
+
+// A weighted graph being coarsened by Louvain. Self loops hold the weight inside merged
+// communities.
+struct Level {
+    neighbors: Vec<BTreeMap<usize, f64>>,
+    loops: Vec<f64>,
+}
+
+impl Level {
+    fn from_adjacency(adjacency: &Adjacency) -> Self {
+        let count = adjacency.vertex_count();
+        let mut neighbors = vec![BTreeMap::new(); count];
+        for (vertex, vertex_neighbors) in neighbors.iter_mut().enumerate() {
+            for &(neighbor, weight) in adjacency.neighbors(vertex) {
+                *vertex_neighbors.entry(neighbor).or_insert(0.0) += weight;
+            }
+        }
+        Level {
+            neighbors,
+            loops: vec![0.0; count],
+        }
+    }
+
+    // Weighted degree with self loops counted twice.
+    fn strength(&self, vertex: usize) -> f64 {
+        self.neighbors[vertex].values().sum::<f64>() + 2.0 * self.loops[vertex]
+    }
+
+    // Local moving phase. Returns each vertex's community and whether anything moved.
+    fn move_vertices(&self) -> (Vec<usize>, bool) {
+        let count = self.neighbors.len();
+        let strength: Vec<f64> = (0..count).map(|vertex| self.strength(vertex)).collect();
+        let total: f64 = strength.iter().sum();
+        let mut community: Vec<usize> = (0..count).collect();
+        let mut community_strength = strength.clone();
+        let mut improved = false;
+
+        if total == 0.0 {
+            return (community, improved);
+        }
+
+        loop {
+            let mut moved = false;
+            for vertex in 0..count {
+                let current = community[vertex];
+                // Weight from the vertex to each neighboring community.
+                let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+                for (&neighbor, &weight) in self.neighbors[vertex].iter() {
+                    *links.entry(community[neighbor]).or_insert(0.0) += weight;
+                }
+
+                community_strength[current] -= strength[vertex];
+                // The gain of joining a community is proportional to
+                // links - community_strength * strength / total
+                let gain = |target: usize, weight: f64| {
+                    weight - community_strength[target] * strength[vertex] / total
+                };
+                let mut best = current;
+                let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+                for (&target, &weight) in links.iter() {
+                    let target_gain = gain(target, weight);
+                    if target_gain > best_gain + MIN_GAIN {
+                        best = target;
+                        best_gain = target_gain;
+                    }
+                }
+                community_strength[best] += strength[vertex];
+
+                if best != current {
+                    community[vertex] = best;
+                    moved = true;
+                    improved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+        (community, improved)
+    }
+
+    // Merges each community into a single vertex. Communities must be numbered 0..count.
+    fn aggregate(&self, community: &[usize], count: usize) -> Self {
+        let mut neighbors = vec![BTreeMap::new(); count];
+        let mut loops = vec![0.0; count];
+        for (vertex, vertex_neighbors) in self.neighbors.iter().enumerate() {
+            let source = community[vertex];
+            loops[source] += self.loops[vertex];
+            for (&neighbor, &weight) in vertex_neighbors.iter() {
+                let target = community[neighbor];
+                if source == target {
+                    // Internal edges are seen from both ends.
+                    loops[source] += weight / 2.0;
+                } else {
+                    *neighbors[source].entry(target).or_insert(0.0) += weight;
+                }
+            }
+        }
+        Level { neighbors, loops }
+    }
+}
+
+// Numbers communities 0..count in order of first appearance.
+fn renumber(community: &mut [usize]) -> usize {
+    let mut numbers: HashMap<usize, usize> = HashMap::new();
+    for label in community.iter_mut() {
+        let next = numbers.len();
+        *label = *numbers.entry(*label).or_insert(next);
+    }
+    numbers.len()
+}
+
+/// Louvain community detection. Returns each vertex's community, numbered from zero.
+pub fn louvain(adjacency: &Adjacency) -> Vec<usize> {
+    let mut membership: Vec<usize> = (0..adjacency.vertex_count()).collect();
+    let mut level = Level::from_adjacency(adjacency);
+
+    loop {
+        let (mut community, improved) = level.move_vertices();
+        if !improved {
+            break;
+        }
+        let count = renumber(&mut community);
+        for label in membership.iter_mut() {
+            *label = community[*label];
+        }
+        level = level.aggregate(&community, count);
+    }
+
+    renumber(&mut membership);
+    membership
+}
+
+/// Newman's modularity of a partition of the graph.
+pub fn modularity(adjacency: &Adjacency, partition: &[usize]) -> f64 {
+    let total: f64 = (0..adjacency.vertex_count())
+        .map(|vertex| adjacency.strength(vertex))
+        .sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let mut inside = 0.0;
+    let mut community_strength: HashMap<usize, f64> = HashMap::new();
+    for (vertex, &community) in partition.iter().enumerate() {
+        *community_strength.entry(community).or_insert(0.0) += adjacency.strength(vertex);
+        for &(neighbor, weight) in adjacency.neighbors(vertex) {
+            if partition[neighbor] == community {
+                inside += weight;
+            }
+        }
+    }
+
+    inside / total
+        - community_strength
+            .values()
+            .map(|strength| (strength / total).powi(2))
+            .sum::<f64>()
+}
+
+// Groups vertices by an optional label. Vertices without a label get a group of their own.
+fn partition_by<'a, F>(count: usize, label: F) -> Vec<usize>
+where
+    F: Fn(usize) -> Option<&'a str>,
+{
+    let mut groups: HashMap<&'a str, usize> = HashMap::new();
+    (0..count)
+        .map(|vertex| match label(vertex) {
+            Some(label) => {
+                let next = count + groups.len();
+                *groups.entry(label).or_insert(next)
+            }
+            None => vertex,
+        })
+        .collect()
+}
+
+// Finds the most common label and its share of the members.
+fn dominant<'a, I>(labels: I, size: usize) -> (Option<String>, f64)
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for label in labels {
+        *counts.entry(label).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map_or((None, 0.0), |(label, count)| {
+            (Some(label.to_owned()), count as f64 / size as f64)
+        })
+}
+
+/// Runs Louvain over the user co-participation graph of a full contributor graph. Returns the
+/// co-participation graph with every user's community set along with a report comparing the
+/// communities against the users' companies and organizations.
+pub fn detect_communities(graph: &ContributorGraph) -> (ContributorGraph, CommunityReport) {
+    let edges = co_participation(graph, SharedBy::PullRequests, Normalization::None);
+    let mut users = graph.projection(VertexKind::User, EdgeKind::CoParticipated, &edges);
+    let adjacency = Adjacency::from_graph(&users);
+    let communities = louvain(&adjacency);
+    users.assign_communities(&communities);
+
+    // Organizations only exist in the full graph so they're looked up by user ID.
+    let mut organizations: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in graph.edges() {
+        if edge.kind == EdgeKind::MemberOf {
+            organizations
+                .entry(graph.vertices()[edge.source].id.as_str())
+                .or_default()
+                .push(graph.vertices()[edge.target].label.as_str());
+        }
+    }
+    let user_organizations: Vec<&[&str]> = users
+        .vertices()
+        .iter()
+        .map(|vertex| {
+            organizations
+                .get(vertex.id.as_str())
+                .map_or(&[][..], |orgs| orgs.as_slice())
+        })
+        .collect();
+
+    let count = users.vertices().len();
+    let company_partition =
+        partition_by(count, |vertex| users.vertices()[vertex].company.as_deref());
+    let organization_partition =
+        partition_by(count, |vertex| user_organizations[vertex].first().copied());
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (vertex, &community) in communities.iter().enumerate() {
+        members.entry(community).or_default().push(vertex);
+    }
+    let mut summaries: Vec<CommunitySummary> = members
+        .into_iter()
+        .map(|(community, members)| {
+            let size = members.len();
+            let (top_company, top_company_share) = dominant(
+                members
+                    .iter()
+                    .filter_map(|&vertex| users.vertices()[vertex].company.as_deref()),
+                size,
+            );
+            let (top_organization, top_organization_share) = dominant(
+                members
+                    .iter()
+                    .flat_map(|&vertex| user_organizations[vertex].iter().copied()),
+                size,
+            );
+            CommunitySummary {
+                community,
+                size,
+                top_company,
+                top_company_share,
+                top_organization,
+                top_organization_share,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.size.cmp(&a.size).then(a.community.cmp(&b.community)));
+
+    let report = CommunityReport {
+        modularity: modularity(&adjacency, &communities),
+        company_modularity: modularity(&adjacency, &company_partition),
+        organization_modularity: modularity(&adjacency, &organization_partition),
+        communities: summaries,
+    };
+    (users, report)
+}
+
+/// Lists every user's community in a graph returned by detect_communities.
+pub fn community_members(graph: &ContributorGraph) -> Vec<CommunityMember> {
+    graph
+        .vertices()
+        .iter()
+        .filter(|vertex| vertex.kind == VertexKind::User)
+        .map(|vertex| CommunityMember {
+            user: vertex.label.clone(),
+            community: vertex.community,
+            company: vertex.company.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Triangles 0-1-2 and 3-4-5 joined by the bridge 2-3.
+    fn bridged_triangles() -> Adjacency {
+        Adjacency::from_edges(
+            6,
+            &[
+                (0, 1, 1.0),
+                (1, 2, 1.0),
+                (2, 0, 1.0),
+                (3, 4, 1.0),
+                (4, 5, 1.0),
+                (5, 3, 1.0),
+                (2, 3, 1.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn louvain_splits_bridged_triangles() {
+        let communities = louvain(&bridged_triangles());
+        assert_eq!(communities, vec![0, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn bridged_triangles_modularity() {
+        // 6 of the 7 edges are inside a community and each community holds half of the degree:
+        // Q = 6 / 7 - 2 * (1 / 2)^2 = 5 / 14.
+        let q = modularity(&bridged_triangles(), &[0, 0, 0, 1, 1, 1]);
+        assert!((q - 5.0 / 14.0).abs() < 1e-9, "got {}", q);
+        // A single community has nothing to gain over the null model.
+        let q = modularity(&bridged_triangles(), &[0; 6]);
+        assert!(q.abs() < 1e-9, "got {}", q);
+    }
+
+    #[test]
+    fn partition_by_labels() {
+        let labels = [Some("a"), None, Some("b"), Some("a")];
+        let partition = partition_by(labels.len(), |vertex| labels[vertex]);
+        assert_eq!(partition[0], partition[3]);
+        assert_ne!(partition[0], partition[2]);
+        // Unlabeled vertices are alone.
+        assert!(
+            partition
+                .iter()
+                .filter(|&&group| group == partition[1])
+                .count()
+                == 1
+        );
+    }
+}
@@ -0,0 +1,577 @@
+#![warn(clippy::all)]
+use super::projection::ProjectedEdge;
+use crate::query_structs::{
+    adoptionview_nodes::{Adoption, AdoptionViewNode},
+    commitview_nodes::{CommitRole, CommitViewNode},
+    discussionview_nodes::{DiscussionInteraction, DiscussionViewNode},
+    issueview_nodes::IssueViewNode,
+    repoview_nodes::{RepoViewNode, Role},
+    review_nodes::{Interaction, ReviewNode},
+    scalars::DateTime,
+};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum VertexKind {
+    User,
+    Repository,
+    PullRequest,
+    Issue,
+    Discussion,
+    Commit,
+    Organization,
+}
+
+impl VertexKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VertexKind::User => "user",
+            VertexKind::Repository => "repository",
+            VertexKind::PullRequest => "pull_request",
+            VertexKind::Issue => "issue",
+            VertexKind::Discussion => "discussion",
+            VertexKind::Commit => "commit",
+            VertexKind::Organization => "organization",
+        }
+    }
+}
+
+/// State of a submitted review. Reviews in different states are kept as separate edges.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    Dismissed,
+    Pending,
+    /// Any state GitHub adds later.
+    Other,
+}
+
+impl ReviewState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewState::Approved => "APPROVED",
+            ReviewState::ChangesRequested => "CHANGES_REQUESTED",
+            ReviewState::Commented => "COMMENTED",
+            ReviewState::Dismissed => "DISMISSED",
+            ReviewState::Pending => "PENDING",
+            ReviewState::Other => "OTHER",
+        }
+    }
+
+    fn from_state(state: &str) -> Self {
+        match state {
+            "APPROVED" => ReviewState::Approved,
+            "CHANGES_REQUESTED" => ReviewState::ChangesRequested,
+            "COMMENTED" => ReviewState::Commented,
+            "DISMISSED" => ReviewState::Dismissed,
+            "PENDING" => ReviewState::Pending,
+            _ => ReviewState::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EdgeKind {
+    /// User -> pull request, issue, or discussion
+    Participated,
+    /// Pull request, issue, discussion, or commit -> repository
+    PartOf,
+    /// User -> organization
+    MemberOf,
+    /// User -> commit, for the commit's author and each co-author
+    Authored,
+    /// User -> commit, if someone besides the author committed it
+    Committed,
+    /// User -> repository
+    Starred,
+    /// User -> repository
+    Watched,
+    /// User (or organization owning the fork) -> repository
+    Forked,
+    /// User -> user, from a pull request's, issue's, or discussion's author to each participant
+    Engaged,
+    /// User -> user, from a pull request's author to each reviewer
+    Reviewed(ReviewState),
+    /// User -> user, from a pull request's author to each review thread commenter
+    ReviewCommented,
+    /// User -> user, from a discussion reply's author to the author of the comment replied to
+    Replied,
+    /// User -> discussion, for the author of the chosen answer
+    Answered,
+    /// User -- user (undirected projection)
+    CoParticipated,
+    /// Repository -- repository (undirected projection)
+    SharedContributors,
+}
+
+impl EdgeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EdgeKind::Participated => "participated",
+            EdgeKind::PartOf => "part_of",
+            EdgeKind::MemberOf => "member_of",
+            EdgeKind::Authored => "authored",
+            EdgeKind::Committed => "committed",
+            EdgeKind::Starred => "starred",
+            EdgeKind::Watched => "watched",
+            EdgeKind::Forked => "forked",
+            EdgeKind::Engaged => "engaged",
+            EdgeKind::Reviewed(_) => "reviewed",
+            EdgeKind::ReviewCommented => "review_commented",
+            EdgeKind::Replied => "replied",
+            EdgeKind::Answered => "answered",
+            EdgeKind::CoParticipated => "co_participated",
+            EdgeKind::SharedContributors => "shared_contributors",
+        }
+    }
+
+    /// Projected edges are undirected.
+    pub fn is_directed(self) -> bool {
+        match self {
+            EdgeKind::Participated
+            | EdgeKind::PartOf
+            | EdgeKind::MemberOf
+            | EdgeKind::Authored
+            | EdgeKind::Committed
+            | EdgeKind::Starred
+            | EdgeKind::Watched
+            | EdgeKind::Forked
+            | EdgeKind::Engaged
+            | EdgeKind::Reviewed(_)
+            | EdgeKind::ReviewCommented
+            | EdgeKind::Replied
+            | EdgeKind::Answered => true,
+            EdgeKind::CoParticipated | EdgeKind::SharedContributors => false,
+        }
+    }
+
+    /// The review state of Reviewed edges.
+    pub fn state(self) -> Option<&'static str> {
+        match self {
+            EdgeKind::Reviewed(state) => Some(state.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// A typed vertex. The optional attributes are only filled in for the kinds they make sense for
+/// (location/company for users and date_created, formatted as RFC 3339, url, and state for pull
+/// requests, issues, and discussions). Community is only set after community detection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vertex {
+    /// Stable identifier such as "user:login" that is unique across every kind.
+    pub id: String,
+    pub kind: VertexKind,
+    pub label: String,
+    pub location: Option<String>,
+    pub company: Option<String>,
+    pub date_created: Option<String>,
+    pub url: Option<String>,
+    pub state: Option<String>,
+    pub community: Option<usize>,
+}
+
+impl Vertex {
+    fn new(kind: VertexKind, key: &str, label: &str) -> Self {
+        Vertex {
+            id: format!("{}:{}", kind.as_str(), key),
+            kind,
+            label: label.to_owned(),
+            location: None,
+            company: None,
+            date_created: None,
+            url: None,
+            state: None,
+            community: None,
+        }
+    }
+}
+
+// The parts of a RepoViewNode, IssueViewNode, or DiscussionViewNode that describe who took part.
+struct Participation<'a> {
+    participant: &'a str,
+    role: Role,
+    author: Option<&'a str>,
+    location: &'a Option<String>,
+    company: &'a Option<String>,
+    organizations: &'a [String],
+}
+
+/// Edges refer to vertices by their index in ContributorGraph::vertices. Repeated edges aren't
+/// duplicated; the weight counts how many times the edge was seen instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge {
+    pub source: usize,
+    pub target: usize,
+    pub kind: EdgeKind,
+    pub weight: u64,
+    /// Normalized weight (Jaccard, cosine, etc.) of projected edges.
+    pub normalized: Option<f64>,
+    /// When the edge itself was created (formatted as RFC 3339) if that isn't simply when its
+    /// pull request or commit was created, such as when a repository was starred or a discussion
+    /// reply was first made.
+    pub date_created: Option<String>,
+}
+
+/// A typed graph of users, repositories, pull requests, issues, discussions, and organizations
+/// built from RepoViewNodes, IssueViewNodes, and DiscussionViewNodes. ReviewNodes add review edges
+/// between users, CommitViewNodes add commits, and AdoptionViewNodes add users that starred,
+/// watched, or forked repositories.
+#[derive(Clone, Debug, Default)]
+pub struct ContributorGraph {
+    vertices: Vec<Vertex>,
+    edges: Vec<Edge>,
+    // Vertex ID to index in vertices.
+    vertex_index: HashMap<String, usize>,
+    // (source, target, kind) to index in edges.
+    edge_index: HashMap<(usize, usize, EdgeKind), usize>,
+}
+
+impl ContributorGraph {
+    pub fn new() -> Self {
+        ContributorGraph::default()
+    }
+
+    pub fn from_nodes(nodes: &[RepoViewNode]) -> Self {
+        let mut graph = ContributorGraph::new();
+        for node in nodes {
+            graph.add_node(node);
+        }
+        graph
+    }
+
+    /// Adds a single RepoViewNode's user, repository, pull request, and organizations along with
+    /// the edges between them.
+    pub fn add_node(&mut self, node: &RepoViewNode) {
+        // Pull requests are keyed by their number. Nodes from before numbers were recorded fall
+        // back on the title and creation date.
+        let key = if node.pr_number > 0 {
+            format!("{}#{}", node.repository, node.pr_number)
+        } else {
+            format!(
+                "{}#{}@{}",
+                node.repository, node.pull_req_title, node.date_created
+            )
+        };
+        let mut pull_request = Vertex::new(VertexKind::PullRequest, &key, &node.pull_req_title);
+        pull_request.date_created = Some(node.date_created.to_string());
+        pull_request.url = node.pr_url.as_ref().map(|url| url.to_string());
+        if !node.state.is_empty() {
+            pull_request.state = Some(node.state.clone());
+        }
+
+        self.add_participation(
+            &node.repository,
+            pull_request,
+            Participation {
+                participant: &node.participant,
+                role: node.role,
+                author: node.pr_author.as_deref(),
+                location: &node.location,
+                company: &node.company,
+                organizations: &node.organizations,
+            },
+        );
+    }
+
+    /// Adds a single IssueViewNode the same way as add_node. Issues share the repository, user,
+    /// and organization vertices with pull requests so projections count both.
+    pub fn add_issue_node(&mut self, node: &IssueViewNode) {
+        let key = format!("{}#{}", node.repository, node.issue_number);
+        let mut issue = Vertex::new(VertexKind::Issue, &key, &node.issue_title);
+        issue.date_created = Some(node.date_created.to_string());
+        issue.url = Some(node.issue_url.to_string());
+        issue.state = Some(node.state.clone());
+
+        self.add_participation(
+            &node.repository,
+            issue,
+            Participation {
+                participant: &node.participant,
+                role: node.role,
+                author: node.issue_author.as_deref(),
+                location: &node.location,
+                company: &node.company,
+                organizations: &node.organizations,
+            },
+        );
+    }
+
+    /// Adds a single DiscussionViewNode the same way as add_issue_node. Discussions have a node
+    /// per comment so a user is only linked to the discussion (and its author) the first time
+    /// they're seen, which keeps projections counting discussions rather than comments. Replies
+    /// add an edge to the author of the comment replied to and the chosen answer an edge to the
+    /// discussion.
+    pub fn add_discussion_node(&mut self, node: &DiscussionViewNode) {
+        let key = format!("{}#{}", node.repository, node.discussion_number);
+        let mut discussion = Vertex::new(VertexKind::Discussion, &key, &node.discussion_title);
+        discussion.date_created = Some(node.date_created.to_string());
+        discussion.url = Some(node.discussion_url.to_string());
+        let discussion_id = discussion.id.clone();
+
+        let user_id = Vertex::new(VertexKind::User, &node.participant, "").id;
+        let participated = match (
+            self.vertex_index.get(&user_id),
+            self.vertex_index.get(&discussion_id),
+        ) {
+            (Some(&user), Some(&item)) => {
+                self.edge_index
+                    .contains_key(&(user, item, EdgeKind::Participated))
+            }
+            _ => false,
+        };
+        if !participated {
+            self.add_participation(
+                &node.repository,
+                discussion,
+                Participation {
+                    participant: &node.participant,
+                    role: node.role,
+                    author: node.discussion_author.as_deref(),
+                    location: &None,
+                    company: &None,
+                    organizations: &[],
+                },
+            );
+        }
+
+        let user = self.vertex_index[&user_id];
+        let edge = match (node.interaction, node.replied_to.as_deref()) {
+            (DiscussionInteraction::Replied, Some(replied_to))
+                if replied_to != node.participant =>
+            {
+                let replied_to =
+                    self.add_vertex(Vertex::new(VertexKind::User, replied_to, replied_to));
+                self.add_edge(user, replied_to, EdgeKind::Replied)
+            }
+            (DiscussionInteraction::Answered, _) => {
+                let discussion = self.vertex_index[&discussion_id];
+                self.add_edge(user, discussion, EdgeKind::Answered)
+            }
+            _ => return,
+        };
+        self.set_earliest_date(edge, node.date);
+    }
+
+    /// Adds an edge from the pull request's author to the reviewer, dated by the earliest
+    /// submission. Authors replying to reviews of their own pull requests and reviews of pull
+    /// requests from deleted accounts are skipped.
+    pub fn add_review_node(&mut self, node: &ReviewNode) {
+        let author = match node.pr_author.as_deref() {
+            Some(author) if author != node.reviewer => author,
+            _ => return,
+        };
+        let kind = match node.interaction {
+            Interaction::Review => EdgeKind::Reviewed(ReviewState::from_state(
+                node.state.as_deref().unwrap_or_default(),
+            )),
+            Interaction::ReviewComment => EdgeKind::ReviewCommented,
+        };
+
+        let author = self.add_vertex(Vertex::new(VertexKind::User, author, author));
+        let reviewer = self.add_vertex(Vertex::new(
+            VertexKind::User,
+            &node.reviewer,
+            &node.reviewer,
+        ));
+        let edge = self.add_edge(author, reviewer, kind);
+        // Pending reviews haven't been submitted so they don't date the edge.
+        if let Some(date) = node.submitted_at {
+            self.set_earliest_date(edge, date);
+        }
+    }
+
+    /// Adds a commit and an edge from its author (or co-author). Authors without a GitHub account
+    /// become user vertices keyed by email so that they're still counted. Commits are dated by
+    /// when they were authored in UTC.
+    pub fn add_commit_node(&mut self, node: &CommitViewNode) {
+        let repository = self.add_vertex(Vertex::new(
+            VertexKind::Repository,
+            &node.repository,
+            &node.repository,
+        ));
+        let mut commit = Vertex::new(
+            VertexKind::Commit,
+            &format!("{}@{}", node.repository, node.oid),
+            &node.message_headline,
+        );
+        commit.date_created = node
+            .authored_date
+            .or(node.committed_date)
+            .map(|date| DateTime::from(date).to_string());
+        commit.url = Some(node.url.to_string());
+        let commit = self.add_vertex(commit);
+        self.add_edge(commit, repository, EdgeKind::PartOf);
+
+        let label = node
+            .author_login
+            .as_ref()
+            .or(node.author_name.as_ref())
+            .unwrap_or(&node.author);
+        let author = self.add_vertex(Vertex::new(VertexKind::User, &node.author, label));
+        self.add_edge(author, commit, EdgeKind::Authored);
+
+        if let (CommitRole::Author, Some(committer)) = (node.role, node.committer.as_deref()) {
+            if committer != node.author {
+                let committer =
+                    self.add_vertex(Vertex::new(VertexKind::User, committer, committer));
+                self.add_edge(committer, commit, EdgeKind::Committed);
+            }
+        }
+    }
+
+    /// Adds an edge from the stargazer, watcher, or fork owner to the repository. Fork owners may
+    /// be organizations but are added as users since they act as one here.
+    pub fn add_adoption_node(&mut self, node: &AdoptionViewNode) {
+        let repository = self.add_vertex(Vertex::new(
+            VertexKind::Repository,
+            &node.repository,
+            &node.repository,
+        ));
+        let user = self.add_vertex(Vertex::new(VertexKind::User, &node.user, &node.user));
+        let kind = match node.adoption {
+            Adoption::Star => EdgeKind::Starred,
+            Adoption::Watch => EdgeKind::Watched,
+            Adoption::Fork => EdgeKind::Forked,
+        };
+        let edge = self.add_edge(user, repository, kind);
+
+        // A user may fork a repository more than once; the edge starts at the first fork.
+        if let Some(date) = node.date {
+            self.set_earliest_date(edge, date);
+        }
+    }
+
+    // Dates an edge by the earliest of the times it was seen.
+    fn set_earliest_date(&mut self, edge: usize, date: DateTime) {
+        let date = date.to_string();
+        let edge = &mut self.edges[edge];
+        match edge.date_created {
+            Some(ref first) if *first <= date => (),
+            _ => edge.date_created = Some(date),
+        }
+    }
+
+    // Links a participant to a pull request, issue, or discussion (item) and the item to its
+    // repository.
+    fn add_participation(&mut self, repository: &str, item: Vertex, participation: Participation) {
+        let repository =
+            self.add_vertex(Vertex::new(VertexKind::Repository, repository, repository));
+        let item = self.add_vertex(item);
+
+        let participant = participation.participant;
+        let user = self.add_vertex(Vertex::new(VertexKind::User, participant, participant));
+        // The user may have been added without attributes as some earlier pull request's author.
+        let vertex = &mut self.vertices[user];
+        if vertex.location.is_none() {
+            vertex.location = participation.location.clone();
+        }
+        if vertex.company.is_none() {
+            vertex.company = participation.company.clone();
+        }
+
+        self.add_edge(item, repository, EdgeKind::PartOf);
+        self.add_edge(user, item, EdgeKind::Participated);
+        if let (Role::Participant, Some(author)) = (participation.role, participation.author) {
+            if author != participant {
+                let author = self.add_vertex(Vertex::new(VertexKind::User, author, author));
+                self.add_edge(author, user, EdgeKind::Engaged);
+            }
+        }
+        for org in participation.organizations.iter() {
+            let org = self.add_vertex(Vertex::new(VertexKind::Organization, org, org));
+            self.add_edge(user, org, EdgeKind::MemberOf);
+        }
+    }
+
+    // Returns the index of the vertex with the same ID if one exists or inserts the vertex.
+    fn add_vertex(&mut self, vertex: Vertex) -> usize {
+        if let Some(&index) = self.vertex_index.get(&vertex.id) {
+            index
+        } else {
+            let index = self.vertices.len();
+            self.vertex_index.insert(vertex.id.clone(), index);
+            self.vertices.push(vertex);
+            index
+        }
+    }
+
+    // Pull requests, issues, and organizations are seen once per participant so the PartOf and
+    // MemberOf edges are only counted once. Engaged edges count the pull requests and issues an
+    // author and participant shared while review and reply edges count each review, comment, or
+    // reply. Returns the edge's index.
+    fn add_edge(&mut self, source: usize, target: usize, kind: EdgeKind) -> usize {
+        match self.edge_index.get(&(source, target, kind)) {
+            Some(&index) if kind == EdgeKind::PartOf || kind == EdgeKind::MemberOf => index,
+            Some(&index) => {
+                self.edges[index].weight += 1;
+                index
+            }
+            None => {
+                let index = self.edges.len();
+                self.edge_index.insert((source, target, kind), index);
+                self.edges.push(Edge {
+                    source,
+                    target,
+                    kind,
+                    weight: 1,
+                    normalized: None,
+                    date_created: None,
+                });
+                index
+            }
+        }
+    }
+
+    /// Builds a graph of the projected edges between this graph's vertices of a single kind so
+    /// that projections may be written by the same writers as the full graph.
+    pub fn projection(
+        &self,
+        vertex_kind: VertexKind,
+        edge_kind: EdgeKind,
+        edges: &[ProjectedEdge],
+    ) -> ContributorGraph {
+        let mut projected = ContributorGraph::new();
+        // Old index -> new index
+        let indices: HashMap<usize, usize> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, vertex)| vertex.kind == vertex_kind)
+            .map(|(index, vertex)| (index, projected.add_vertex(vertex.clone())))
+            .collect();
+
+        for edge in edges {
+            let (source, target) = (indices[&edge.source], indices[&edge.target]);
+            projected
+                .edge_index
+                .insert((source, target, edge_kind), projected.edges.len());
+            projected.edges.push(Edge {
+                source,
+                target,
+                kind: edge_kind,
+                weight: edge.shared,
+                normalized: Some(edge.weight),
+                date_created: None,
+            });
+        }
+        projected
+    }
+
+    /// Sets each vertex's community from a partition indexed like vertices().
+    pub fn assign_communities(&mut self, communities: &[usize]) {
+        for (vertex, &community) in self.vertices.iter_mut().zip(communities.iter()) {
+            vertex.community = Some(community);
+        }
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+}
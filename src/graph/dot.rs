@@ -0,0 +1,247 @@
+#![warn(clippy::all)]
+use super::{
+    contributor_graph::{ContributorGraph, EdgeKind, VertexKind},
+    projection::{co_participation, Normalization, SharedBy},
+};
+use crate::error::{Error, ErrorKind, Result};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    str::FromStr,
+};
+
+/// Which graph the DOT file draws.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DotView {
+    /// Every vertex and edge of the contributor graph.
+    Full,
+    /// Users only, connected by the number of pull requests they share.
+    CoParticipation,
+}
+
+impl FromStr for DotView {
+    type Err = Error;
+
+    fn from_str(view: &str) -> Result<Self> {
+        match view.to_lowercase().as_str() {
+            "full" => Ok(DotView::Full),
+            "coparticipation" => Ok(DotView::CoParticipation),
+            _ => Err(Error::new(
+                format!("Unknown DOT view ({})", view),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+/// How users are grouped into Graphviz clusters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Clustering {
+    None,
+    Company,
+    /// Users belonging to several organizations are placed in the first one listed since
+    /// clusters can't overlap.
+    Organization,
+    /// Detected communities (see the communities subcommand).
+    Community,
+}
+
+impl FromStr for Clustering {
+    type Err = Error;
+
+    fn from_str(cluster: &str) -> Result<Self> {
+        match cluster.to_lowercase().as_str() {
+            "none" => Ok(Clustering::None),
+            "company" => Ok(Clustering::Company),
+            "organization" | "org" => Ok(Clustering::Organization),
+            "community" => Ok(Clustering::Community),
+            _ => Err(Error::new(
+                format!("Unknown clustering ({})", cluster),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DotOptions {
+    pub view: DotView,
+    /// Edges lighter than this are left out.
+    pub min_weight: u64,
+    pub clustering: Clustering,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        DotOptions {
+            view: DotView::Full,
+            min_weight: 1,
+            clustering: Clustering::None,
+        }
+    }
+}
+
+// DOT IDs are quoted so that logins, titles, and companies may contain anything.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn shape(kind: VertexKind) -> &'static str {
+    match kind {
+        VertexKind::User => "ellipse",
+        VertexKind::Repository => "box",
+        VertexKind::PullRequest => "note",
+        VertexKind::Issue => "component",
+        VertexKind::Discussion => "tab",
+        VertexKind::Commit => "circle",
+        VertexKind::Organization => "hexagon",
+    }
+}
+
+// Maps each user vertex to the name of its cluster, if any.
+fn clusters(
+    graph: &ContributorGraph,
+    source: &ContributorGraph,
+    clustering: Clustering,
+) -> BTreeMap<usize, String> {
+    let mut clusters = BTreeMap::new();
+    match clustering {
+        Clustering::None => (),
+        Clustering::Company => {
+            for (index, vertex) in graph.vertices().iter().enumerate() {
+                if let (VertexKind::User, Some(company)) = (vertex.kind, &vertex.company) {
+                    clusters.insert(index, company.clone());
+                }
+            }
+        }
+        Clustering::Organization => {
+            // Projections have no MemberOf edges so organizations are looked up by user ID in the
+            // graph they were projected from.
+            let mut organizations: HashMap<&str, &str> = HashMap::new();
+            for edge in source.edges() {
+                if edge.kind == EdgeKind::MemberOf {
+                    organizations
+                        .entry(source.vertices()[edge.source].id.as_str())
+                        .or_insert_with(|| source.vertices()[edge.target].label.as_str());
+                }
+            }
+            for (index, vertex) in graph.vertices().iter().enumerate() {
+                if let Some(organization) = organizations.get(vertex.id.as_str()) {
+                    clusters.insert(index, (*organization).to_owned());
+                }
+            }
+        }
+        Clustering::Community => {
+            for (index, vertex) in graph.vertices().iter().enumerate() {
+                if let Some(community) = vertex.community {
+                    clusters.insert(index, format!("community {}", community));
+                }
+            }
+        }
+    }
+    clusters
+}
+
+/// Writes the graph in Graphviz's DOT language for quick visual checks. Organization clusters are
+/// taken from source, which is the graph itself unless it's a projection.
+pub fn write_dot<W>(
+    mut writer: W,
+    graph: &ContributorGraph,
+    source: &ContributorGraph,
+    options: &DotOptions,
+) -> Result<()>
+where
+    W: Write,
+{
+    let vertices = graph.vertices();
+
+    // (source, target, weight, directed) of every edge that passes the weight filter.
+    let edges: Vec<(usize, usize, u64, bool)> = match options.view {
+        DotView::Full => graph
+            .edges()
+            .iter()
+            .map(|edge| {
+                (
+                    edge.source,
+                    edge.target,
+                    edge.weight,
+                    edge.kind.is_directed(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        DotView::CoParticipation => {
+            co_participation(graph, SharedBy::PullRequests, Normalization::None)
+                .into_iter()
+                .map(|edge| (edge.source, edge.target, edge.shared, false))
+                .collect::<Vec<_>>()
+        }
+    }
+    .into_iter()
+    .filter(|&(_, _, weight, _)| weight >= options.min_weight)
+    .collect();
+
+    let (header, connector) = match options.view {
+        DotView::Full => ("digraph", "->"),
+        DotView::CoParticipation => ("graph", "--"),
+    };
+    writeln!(writer, "{} contributors {{", header)?;
+
+    // Clustered users are written inside their cluster; everything else is written at the top
+    // level. The co-participation view only contains users.
+    let clusters = clusters(graph, source, options.clustering);
+    let mut grouped: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, vertex) in vertices.iter().enumerate() {
+        if options.view == DotView::CoParticipation && vertex.kind != VertexKind::User {
+            continue;
+        }
+        grouped
+            .entry(clusters.get(&index).map_or("", String::as_str))
+            .or_default()
+            .push(index);
+    }
+
+    for (cluster_id, (cluster, members)) in grouped.iter().enumerate() {
+        let indent = if cluster.is_empty() {
+            "  "
+        } else {
+            writeln!(writer, "  subgraph cluster_{} {{", cluster_id)?;
+            writeln!(writer, "    label={};", quote(cluster))?;
+            "    "
+        };
+        for &index in members {
+            writeln!(
+                writer,
+                "{}{} [label={}, shape={}];",
+                indent,
+                quote(&vertices[index].id),
+                quote(&vertices[index].label),
+                shape(vertices[index].kind)
+            )?;
+        }
+        if !cluster.is_empty() {
+            writeln!(writer, "  }}")?;
+        }
+    }
+
+    for (source, target, weight, directed) in edges {
+        writeln!(
+            writer,
+            "  {} {} {} [weight={}, penwidth={}{}];",
+            quote(&vertices[source].id),
+            connector,
+            quote(&vertices[target].id),
+            weight,
+            // Keep heavy edges visible without drowning out the rest of the graph.
+            1.0 + (weight as f64).ln(),
+            // Projected edges drawn in a digraph don't get arrowheads.
+            if directed || options.view == DotView::CoParticipation {
+                ""
+            } else {
+                ", dir=none"
+            }
+        )?;
+    }
+
+    writeln!(writer, "}}")?;
+    Ok(writer.flush()?)
+}
@@ -0,0 +1,28 @@
+#![warn(clippy::all)]
+use super::{contributor_graph::ContributorGraph, projection::ProjectedEdge};
+use crate::error::Result;
+use csv::Writer;
+use std::io::Write;
+
+/// Writes a projection as a weighted CSV edge list of logins.
+pub fn write_edge_list<W>(
+    writer: W,
+    graph: &ContributorGraph,
+    edges: &[ProjectedEdge],
+) -> Result<()>
+where
+    W: Write,
+{
+    let vertices = graph.vertices();
+    let mut csv_writer = Writer::from_writer(writer);
+    csv_writer.write_record(["source", "target", "shared", "weight"])?;
+    for edge in edges {
+        csv_writer.write_record([
+            vertices[edge.source].label.as_str(),
+            vertices[edge.target].label.as_str(),
+            edge.shared.to_string().as_str(),
+            edge.weight.to_string().as_str(),
+        ])?;
+    }
+    Ok(csv_writer.flush()?)
+}
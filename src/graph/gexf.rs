@@ -0,0 +1,207 @@
+#![warn(clippy::all)]
+use super::{
+    contributor_graph::{ContributorGraph, EdgeKind},
+    xml::escape,
+};
+use crate::error::Result;
+use std::io::Write;
+
+// (id, title, type) of the node attributes.
+const NODE_ATTRIBUTES: [(&str, &str, &str); 7] = [
+    ("0", "kind", "string"),
+    ("1", "location", "string"),
+    ("2", "company", "string"),
+    ("3", "date_created", "string"),
+    ("4", "community", "integer"),
+    ("5", "url", "string"),
+    ("6", "state", "string"),
+];
+
+// Returns the time that each edge appears on the timeline. Participation, authorship, and pull
+// request (or issue, discussion, or commit) edges start when the item was created. Stars and
+// forks start when the repository was starred or forked while discussion replies and answers and
+// review edges start when they were first made. Watchers, memberships, author to participant
+// edges (which may span several pull requests), and projected edges have no known start so they
+// span the entire timeline.
+fn edge_starts(graph: &ContributorGraph) -> Vec<Option<&str>> {
+    let vertices = graph.vertices();
+    graph
+        .edges()
+        .iter()
+        .map(|edge| match edge.kind {
+            EdgeKind::Participated | EdgeKind::Authored | EdgeKind::Committed => {
+                vertices[edge.target].date_created.as_deref()
+            }
+            EdgeKind::PartOf => vertices[edge.source].date_created.as_deref(),
+            EdgeKind::Starred
+            | EdgeKind::Watched
+            | EdgeKind::Forked
+            | EdgeKind::Replied
+            | EdgeKind::Answered
+            | EdgeKind::Reviewed(_)
+            | EdgeKind::ReviewCommented => edge.date_created.as_deref(),
+            EdgeKind::MemberOf
+            | EdgeKind::Engaged
+            | EdgeKind::CoParticipated
+            | EdgeKind::SharedContributors => None,
+        })
+        .collect()
+}
+
+// Vertices start at their earliest edge so that users, repositories, and organizations appear
+// on the timeline alongside their first pull request. RFC 3339 timestamps in UTC sort correctly
+// as strings.
+fn vertex_starts<'a>(
+    graph: &'a ContributorGraph,
+    edge_starts: &[Option<&'a str>],
+) -> Vec<Option<&'a str>> {
+    let mut starts: Vec<Option<&str>> = graph
+        .vertices()
+        .iter()
+        .map(|vertex| vertex.date_created.as_deref())
+        .collect();
+
+    for (edge, start) in graph.edges().iter().zip(edge_starts.iter()) {
+        if let Some(start) = start {
+            for &index in [edge.source, edge.target].iter() {
+                match starts[index] {
+                    Some(current) if current <= *start => (),
+                    _ => starts[index] = Some(*start),
+                }
+            }
+        }
+    }
+    starts
+}
+
+// Formats an optional start attribute.
+fn start_attr(start: Option<&str>) -> String {
+    start
+        .map(|start| format!(" start=\"{}\"", escape(start)))
+        .unwrap_or_default()
+}
+
+/// Writes the graph as a dynamic GEXF 1.2 document. Edges are time stamped by the pull request's
+/// creation date so Gephi's timeline can animate the contributor network's growth.
+pub fn write_gexf<W>(mut writer: W, graph: &ContributorGraph) -> Result<()>
+where
+    W: Write,
+{
+    let edge_starts = edge_starts(graph);
+    let vertex_starts = vertex_starts(graph, &edge_starts);
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<gexf xmlns=\"http://www.gexf.net/1.2draft\" version=\"1.2\">"
+    )?;
+    writeln!(
+        writer,
+        "  <graph mode=\"dynamic\" defaultedgetype=\"directed\" timeformat=\"dateTime\">"
+    )?;
+
+    writeln!(writer, "    <attributes class=\"node\">")?;
+    for (id, title, attr_type) in NODE_ATTRIBUTES.iter() {
+        writeln!(
+            writer,
+            "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>",
+            id, title, attr_type
+        )?;
+    }
+    writeln!(writer, "    </attributes>")?;
+    writeln!(writer, "    <attributes class=\"edge\">")?;
+    writeln!(
+        writer,
+        "      <attribute id=\"0\" title=\"kind\" type=\"string\"/>"
+    )?;
+    writeln!(
+        writer,
+        "      <attribute id=\"1\" title=\"normalized\" type=\"double\"/>"
+    )?;
+    writeln!(
+        writer,
+        "      <attribute id=\"2\" title=\"state\" type=\"string\"/>"
+    )?;
+    writeln!(writer, "    </attributes>")?;
+
+    writeln!(writer, "    <nodes>")?;
+    for (vertex, start) in graph.vertices().iter().zip(vertex_starts) {
+        writeln!(
+            writer,
+            "      <node id=\"{}\" label=\"{}\"{}>",
+            escape(&vertex.id),
+            escape(&vertex.label),
+            start_attr(start)
+        )?;
+        writeln!(writer, "        <attvalues>")?;
+        let community = vertex.community.map(|community| community.to_string());
+        let values = [
+            Some(vertex.kind.as_str()),
+            vertex.location.as_deref(),
+            vertex.company.as_deref(),
+            vertex.date_created.as_deref(),
+            community.as_deref(),
+            vertex.url.as_deref(),
+            vertex.state.as_deref(),
+        ];
+        for ((id, _, _), value) in NODE_ATTRIBUTES.iter().zip(values.iter()) {
+            if let Some(value) = value {
+                writeln!(
+                    writer,
+                    "          <attvalue for=\"{}\" value=\"{}\"/>",
+                    id,
+                    escape(value)
+                )?;
+            }
+        }
+        writeln!(writer, "        </attvalues>")?;
+        writeln!(writer, "      </node>")?;
+    }
+    writeln!(writer, "    </nodes>")?;
+
+    let vertices = graph.vertices();
+    writeln!(writer, "    <edges>")?;
+    for (index, (edge, start)) in graph.edges().iter().zip(edge_starts).enumerate() {
+        writeln!(
+            writer,
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\" type=\"{}\" weight=\"{}\"{}>",
+            index,
+            escape(&vertices[edge.source].id),
+            escape(&vertices[edge.target].id),
+            if edge.kind.is_directed() {
+                "directed"
+            } else {
+                "undirected"
+            },
+            edge.weight,
+            start_attr(start)
+        )?;
+        writeln!(writer, "        <attvalues>")?;
+        writeln!(
+            writer,
+            "          <attvalue for=\"0\" value=\"{}\"/>",
+            edge.kind.as_str()
+        )?;
+        if let Some(normalized) = edge.normalized {
+            writeln!(
+                writer,
+                "          <attvalue for=\"1\" value=\"{}\"/>",
+                normalized
+            )?;
+        }
+        if let Some(state) = edge.kind.state() {
+            writeln!(
+                writer,
+                "          <attvalue for=\"2\" value=\"{}\"/>",
+                state
+            )?;
+        }
+        writeln!(writer, "        </attvalues>")?;
+        writeln!(writer, "      </edge>")?;
+    }
+    writeln!(writer, "    </edges>")?;
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</gexf>")?;
+    Ok(writer.flush()?)
+}
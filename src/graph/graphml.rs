@@ -0,0 +1,115 @@
+#![warn(clippy::all)]
+use super::{contributor_graph::ContributorGraph, xml::escape};
+use crate::error::Result;
+use std::io::Write;
+
+// (id, for, attr.name, attr.type)
+const KEYS: [(&str, &str, &str, &str); 13] = [
+    ("v_kind", "node", "kind", "string"),
+    ("v_label", "node", "label", "string"),
+    ("v_location", "node", "location", "string"),
+    ("v_company", "node", "company", "string"),
+    ("v_date_created", "node", "date_created", "string"),
+    ("v_url", "node", "url", "string"),
+    ("v_state", "node", "state", "string"),
+    ("v_community", "node", "community", "int"),
+    ("e_kind", "edge", "kind", "string"),
+    ("e_weight", "edge", "weight", "long"),
+    ("e_normalized", "edge", "normalized", "double"),
+    ("e_state", "edge", "state", "string"),
+    ("e_date_created", "edge", "date_created", "string"),
+];
+
+// Writes a <data> element if the attribute exists.
+fn write_data<W>(writer: &mut W, key: &str, value: Option<&str>) -> Result<()>
+where
+    W: Write,
+{
+    if let Some(value) = value {
+        writeln!(
+            writer,
+            "      <data key=\"{}\">{}</data>",
+            key,
+            escape(value)
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the graph as GraphML which Gephi, Cytoscape, NetworkX, and igraph can load directly.
+/// Edges are directed from users to pull requests to repositories, from users to
+/// organizations, and from pull request authors to participants and reviewers. Projected edges
+/// are undirected.
+pub fn write_graphml<W>(mut writer: W, graph: &ContributorGraph) -> Result<()>
+where
+    W: Write,
+{
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+         http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">"
+    )?;
+    for (id, domain, name, attr_type) in KEYS.iter() {
+        writeln!(
+            writer,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            id, domain, name, attr_type
+        )?;
+    }
+    writeln!(writer, "  <graph id=\"G\" edgedefault=\"directed\">")?;
+
+    for vertex in graph.vertices() {
+        writeln!(writer, "    <node id=\"{}\">", escape(&vertex.id))?;
+        write_data(&mut writer, "v_kind", Some(vertex.kind.as_str()))?;
+        write_data(&mut writer, "v_label", Some(&vertex.label))?;
+        write_data(&mut writer, "v_location", vertex.location.as_deref())?;
+        write_data(&mut writer, "v_company", vertex.company.as_deref())?;
+        write_data(
+            &mut writer,
+            "v_date_created",
+            vertex.date_created.as_deref(),
+        )?;
+        write_data(&mut writer, "v_url", vertex.url.as_deref())?;
+        write_data(&mut writer, "v_state", vertex.state.as_deref())?;
+        write_data(
+            &mut writer,
+            "v_community",
+            vertex
+                .community
+                .map(|community| community.to_string())
+                .as_deref(),
+        )?;
+        writeln!(writer, "    </node>")?;
+    }
+
+    let vertices = graph.vertices();
+    for (index, edge) in graph.edges().iter().enumerate() {
+        writeln!(
+            writer,
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\" directed=\"{}\">",
+            index,
+            escape(&vertices[edge.source].id),
+            escape(&vertices[edge.target].id),
+            edge.kind.is_directed()
+        )?;
+        write_data(&mut writer, "e_kind", Some(edge.kind.as_str()))?;
+        write_data(&mut writer, "e_weight", Some(&edge.weight.to_string()))?;
+        write_data(
+            &mut writer,
+            "e_normalized",
+            edge.normalized
+                .map(|normalized| normalized.to_string())
+                .as_deref(),
+        )?;
+        write_data(&mut writer, "e_state", edge.kind.state())?;
+        write_data(&mut writer, "e_date_created", edge.date_created.as_deref())?;
+        writeln!(writer, "    </edge>")?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(writer.flush()?)
+}
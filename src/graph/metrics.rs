@@ -0,0 +1,319 @@
+#![warn(clippy::all)]
+use super::{adjacency::Adjacency, contributor_graph::ContributorGraph};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+const PAGERANK_DAMPING: f64 = 0.85;
+const PAGERANK_ITERATIONS: usize = 100;
+const PAGERANK_TOLERANCE: f64 = 1e-10;
+
+/// Centrality and clustering measures for a single vertex.
+#[derive(Clone, Debug, Serialize)]
+pub struct VertexMetrics {
+    pub user: String,
+    pub degree: usize,
+    /// Sum of edge weights.
+    pub weighted_degree: f64,
+    /// Normalized by the number of pairs of other vertices.
+    pub betweenness: f64,
+    /// Wasserman and Faust's closeness which scales by the share of reachable vertices so that
+    /// vertices in small components don't look central.
+    pub closeness: f64,
+    pub pagerank: f64,
+    pub clustering: f64,
+    /// Index into MetricsSummary::component_sizes.
+    pub component: usize,
+}
+
+/// Graph wide measures.
+#[derive(Clone, Debug, Serialize)]
+pub struct MetricsSummary {
+    pub vertices: usize,
+    pub edges: usize,
+    pub density: f64,
+    pub average_clustering: f64,
+    pub components: usize,
+    /// Largest component first.
+    pub component_sizes: Vec<usize>,
+}
+
+// Labels each vertex with its connected component. Components are numbered largest first.
+fn connected_components(adjacency: &Adjacency) -> (Vec<usize>, Vec<usize>) {
+    let count = adjacency.vertex_count();
+    let mut labels = vec![usize::MAX; count];
+    let mut sizes = Vec::new();
+
+    for start in 0..count {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+        let label = sizes.len();
+        let mut size = 0;
+        let mut queue = VecDeque::new();
+        labels[start] = label;
+        queue.push_back(start);
+        while let Some(vertex) = queue.pop_front() {
+            size += 1;
+            for &(neighbor, _) in adjacency.neighbors(vertex) {
+                if labels[neighbor] == usize::MAX {
+                    labels[neighbor] = label;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    // Renumber so that the largest component is 0.
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]).then(a.cmp(&b)));
+    let mut renumbered = vec![0; sizes.len()];
+    for (new, &old) in order.iter().enumerate() {
+        renumbered[old] = new;
+    }
+    (
+        labels.into_iter().map(|label| renumbered[label]).collect(),
+        order.into_iter().map(|old| sizes[old]).collect(),
+    )
+}
+
+// Brandes' algorithm for unweighted betweenness alongside closeness since both need a breadth
+// first search from every vertex.
+fn betweenness_closeness(adjacency: &Adjacency) -> (Vec<f64>, Vec<f64>) {
+    let count = adjacency.vertex_count();
+    let mut betweenness = vec![0.0; count];
+    let mut closeness = vec![0.0; count];
+
+    for source in 0..count {
+        let mut stack = Vec::with_capacity(count);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); count];
+        let mut paths = vec![0.0; count];
+        let mut distance: Vec<Option<usize>> = vec![None; count];
+        paths[source] = 1.0;
+        distance[source] = Some(0);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(vertex) = queue.pop_front() {
+            stack.push(vertex);
+            let next = distance[vertex].unwrap_or(0) + 1;
+            for &(neighbor, _) in adjacency.neighbors(vertex) {
+                if distance[neighbor].is_none() {
+                    distance[neighbor] = Some(next);
+                    queue.push_back(neighbor);
+                }
+                if distance[neighbor] == Some(next) {
+                    paths[neighbor] += paths[vertex];
+                    predecessors[neighbor].push(vertex);
+                }
+            }
+        }
+
+        // The stack holds every reachable vertex including the source.
+        let reachable = stack.len();
+        let total_distance: usize = distance.iter().flatten().sum();
+        if total_distance > 0 && count > 1 {
+            let reached = (reachable - 1) as f64;
+            closeness[source] = (reached / total_distance as f64) * (reached / (count - 1) as f64);
+        }
+
+        let mut dependency = vec![0.0; count];
+        while let Some(vertex) = stack.pop() {
+            for &predecessor in predecessors[vertex].iter() {
+                dependency[predecessor] +=
+                    paths[predecessor] / paths[vertex] * (1.0 + dependency[vertex]);
+            }
+            if vertex != source {
+                betweenness[vertex] += dependency[vertex];
+            }
+        }
+    }
+
+    // Each pair was counted from both ends.
+    if count > 2 {
+        let scale = 1.0 / ((count - 1) * (count - 2)) as f64;
+        for value in betweenness.iter_mut() {
+            *value *= scale;
+        }
+    }
+    (betweenness, closeness)
+}
+
+// Weighted PageRank by power iteration. Isolated vertices spread their rank evenly.
+fn pagerank(adjacency: &Adjacency) -> Vec<f64> {
+    let count = adjacency.vertex_count();
+    if count == 0 {
+        return Vec::new();
+    }
+    let strength: Vec<f64> = (0..count)
+        .map(|vertex| adjacency.strength(vertex))
+        .collect();
+    let mut rank = vec![1.0 / count as f64; count];
+
+    for _ in 0..PAGERANK_ITERATIONS {
+        let dangling: f64 = (0..count)
+            .filter(|&vertex| strength[vertex] == 0.0)
+            .map(|vertex| rank[vertex])
+            .sum();
+        let base =
+            (1.0 - PAGERANK_DAMPING) / count as f64 + PAGERANK_DAMPING * dangling / count as f64;
+        let mut next = vec![base; count];
+        for vertex in 0..count {
+            if strength[vertex] > 0.0 {
+                for &(neighbor, weight) in adjacency.neighbors(vertex) {
+                    next[neighbor] += PAGERANK_DAMPING * rank[vertex] * weight / strength[vertex];
+                }
+            }
+        }
+
+        let change: f64 = next
+            .iter()
+            .zip(rank.iter())
+            .map(|(new, old)| (new - old).abs())
+            .sum();
+        rank = next;
+        if change < PAGERANK_TOLERANCE {
+            break;
+        }
+    }
+    rank
+}
+
+// Local (unweighted) clustering coefficient.
+fn clustering(adjacency: &Adjacency) -> Vec<f64> {
+    let neighbor_sets: Vec<HashSet<usize>> = (0..adjacency.vertex_count())
+        .map(|vertex| {
+            adjacency
+                .neighbors(vertex)
+                .iter()
+                .map(|&(neighbor, _)| neighbor)
+                .collect()
+        })
+        .collect();
+
+    neighbor_sets
+        .iter()
+        .map(|neighbors| {
+            let degree = neighbors.len();
+            if degree < 2 {
+                return 0.0;
+            }
+            let links: usize = neighbors
+                .iter()
+                .map(|neighbor| neighbor_sets[*neighbor].intersection(neighbors).count())
+                .sum();
+            // Each triangle was counted from both of the other two vertices.
+            links as f64 / (degree * (degree - 1)) as f64
+        })
+        .collect()
+}
+
+/// Computes per vertex metrics and a summary of the whole graph. The graph is treated as
+/// undirected with edge weights used for the weighted degree and PageRank.
+pub fn compute_metrics(graph: &ContributorGraph) -> (Vec<VertexMetrics>, MetricsSummary) {
+    let adjacency = Adjacency::from_graph(graph);
+    let count = adjacency.vertex_count();
+    let (components, component_sizes) = connected_components(&adjacency);
+    let (betweenness, closeness) = betweenness_closeness(&adjacency);
+    let pagerank = pagerank(&adjacency);
+    let clustering = clustering(&adjacency);
+
+    let metrics = graph
+        .vertices()
+        .iter()
+        .enumerate()
+        .map(|(index, vertex)| VertexMetrics {
+            user: vertex.label.clone(),
+            degree: adjacency.degree(index),
+            weighted_degree: adjacency.strength(index),
+            betweenness: betweenness[index],
+            closeness: closeness[index],
+            pagerank: pagerank[index],
+            clustering: clustering[index],
+            component: components[index],
+        })
+        .collect();
+
+    let summary = MetricsSummary {
+        vertices: count,
+        edges: adjacency.edge_count(),
+        density: if count > 1 {
+            2.0 * adjacency.edge_count() as f64 / (count * (count - 1)) as f64
+        } else {
+            0.0
+        },
+        average_clustering: if count > 0 {
+            clustering.iter().sum::<f64>() / count as f64
+        } else {
+            0.0
+        },
+        components: component_sizes.len(),
+        component_sizes,
+    };
+
+    (metrics, summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vertex 0 is the hub of a star with four leaves.
+    fn star() -> Adjacency {
+        Adjacency::from_edges(5, &[(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0), (0, 4, 1.0)])
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn star_betweenness_and_closeness() {
+        let (betweenness, closeness) = betweenness_closeness(&star());
+        // Every one of the six pairs of leaves goes through the hub.
+        assert_close(betweenness[0], 1.0);
+        assert_close(closeness[0], 1.0);
+        for leaf in 1..5 {
+            assert_close(betweenness[leaf], 0.0);
+            // One step to the hub and two to each of the other three leaves.
+            assert_close(closeness[leaf], 4.0 / 7.0);
+        }
+    }
+
+    #[test]
+    fn star_pagerank() {
+        let rank = pagerank(&star());
+        // hub = (1 - d) / 5 + d * (1 - hub) since the leaves give the hub all of their rank.
+        let hub = ((1.0 - PAGERANK_DAMPING) / 5.0 + PAGERANK_DAMPING) / (1.0 + PAGERANK_DAMPING);
+        assert_close(rank[0], hub);
+        for &leaf in rank[1..].iter() {
+            assert_close(leaf, (1.0 - hub) / 4.0);
+        }
+        assert_close(rank.iter().sum(), 1.0);
+    }
+
+    #[test]
+    fn triangle_with_tail_clustering() {
+        let adjacency =
+            Adjacency::from_edges(4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0)]);
+        let coefficients = clustering(&adjacency);
+        assert_close(coefficients[0], 1.0);
+        assert_close(coefficients[1], 1.0);
+        // One of the three pairs of vertex 2's neighbors is connected.
+        assert_close(coefficients[2], 1.0 / 3.0);
+        assert_close(coefficients[3], 0.0);
+    }
+
+    #[test]
+    fn components_largest_first() {
+        let adjacency = Adjacency::from_edges(5, &[(3, 4, 1.0), (0, 1, 1.0), (1, 2, 1.0)]);
+        let (labels, sizes) = connected_components(&adjacency);
+        assert_eq!(labels, vec![0, 0, 0, 1, 1]);
+        assert_eq!(sizes, vec![3, 2]);
+    }
+}
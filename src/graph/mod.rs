@@ -0,0 +1,11 @@
+pub mod adjacency;
+pub mod community;
+pub mod contributor_graph;
+pub mod dot;
+pub mod edge_list;
+pub mod gexf;
+pub mod graphml;
+pub mod metrics;
+pub mod projection;
+pub mod snapshots;
+mod xml;
@@ -0,0 +1,182 @@
+#![warn(clippy::all)]
+use super::contributor_graph::{ContributorGraph, EdgeKind, VertexKind};
+use crate::error::{Error, ErrorKind, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
+
+/// What two users must share to be connected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SharedBy {
+    PullRequests,
+    Repositories,
+}
+
+impl FromStr for SharedBy {
+    type Err = Error;
+
+    fn from_str(shared_by: &str) -> Result<Self> {
+        match shared_by.to_lowercase().as_str() {
+            "pr" | "pullrequests" => Ok(SharedBy::PullRequests),
+            "repo" | "repositories" => Ok(SharedBy::Repositories),
+            _ => Err(Error::new(
+                format!("Unknown projection unit ({})", shared_by),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+/// How the shared count is turned into an edge weight. The "items" are pull requests or
+/// repositories for user projections and contributors for repository projections.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Normalization {
+    /// The raw number of shared items.
+    None,
+    /// Shared items divided by the items either end participated in.
+    Jaccard,
+    /// Shared items divided by the geometric mean of both ends' item counts.
+    Cosine,
+    /// Newman's collaboration weighting: each shared item adds 1 / (members - 1), so large
+    /// items count for less than small ones.
+    Newman,
+}
+
+impl FromStr for Normalization {
+    type Err = Error;
+
+    fn from_str(normalization: &str) -> Result<Self> {
+        match normalization.to_lowercase().as_str() {
+            "none" => Ok(Normalization::None),
+            "jaccard" => Ok(Normalization::Jaccard),
+            "cosine" => Ok(Normalization::Cosine),
+            "newman" => Ok(Normalization::Newman),
+            _ => Err(Error::new(
+                format!("Unknown normalization ({})", normalization),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+/// An undirected edge between two users or two repositories. Source and target index into the
+/// vertices of the ContributorGraph that the projection was built from with source < target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectedEdge {
+    pub source: usize,
+    pub target: usize,
+    /// Number of items both ends share.
+    pub shared: u64,
+    pub weight: f64,
+}
+
+// Groups users by the pull requests or repositories they participated in. Users are deduplicated
+// per item since a user participating in several pull requests of the same repository only
+// counts once for that repository.
+fn participants(graph: &ContributorGraph, shared_by: SharedBy) -> HashMap<usize, BTreeSet<usize>> {
+    // Pull request -> repository
+    let repositories: HashMap<usize, usize> = graph
+        .edges()
+        .iter()
+        .filter(|edge| edge.kind == EdgeKind::PartOf)
+        .map(|edge| (edge.source, edge.target))
+        .collect();
+
+    let mut participants: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for edge in graph.edges() {
+        if edge.kind == EdgeKind::Participated
+            && graph.vertices()[edge.source].kind == VertexKind::User
+        {
+            // Issues and discussions are participated in too but only pull requests count here.
+            let item = match shared_by {
+                SharedBy::PullRequests => Some(edge.target)
+                    .filter(|&target| graph.vertices()[target].kind == VertexKind::PullRequest),
+                SharedBy::Repositories => repositories.get(&edge.target).copied(),
+            };
+            if let Some(item) = item {
+                participants.entry(item).or_default().insert(edge.source);
+            }
+        }
+    }
+    participants
+}
+
+// Connects every pair of members that belong to the same item. For example, items may be pull
+// requests with their participants as members or users with the repositories they contributed to
+// as members.
+fn project(
+    groups: &HashMap<usize, BTreeSet<usize>>,
+    normalization: Normalization,
+) -> Vec<ProjectedEdge> {
+    // Number of items each member belongs to for the Jaccard index and cosine similarity.
+    let mut degree: HashMap<usize, u64> = HashMap::new();
+    // A BTreeMap keeps the output ordered which makes diffs of the exports readable.
+    // (source, target) -> (shared, Newman's weight)
+    let mut shared: BTreeMap<(usize, usize), (u64, f64)> = BTreeMap::new();
+    for members in groups.values() {
+        for &member in members.iter() {
+            *degree.entry(member).or_insert(0) += 1;
+        }
+        // Items with a single member don't connect anyone so the division is safe.
+        let newman = 1.0 / (members.len() as f64 - 1.0);
+        // Members are sorted so first < second.
+        let members: Vec<usize> = members.iter().copied().collect();
+        for (i, &first) in members.iter().enumerate() {
+            for &second in members[i + 1..].iter() {
+                let entry = shared.entry((first, second)).or_insert((0, 0.0));
+                entry.0 += 1;
+                entry.1 += newman;
+            }
+        }
+    }
+
+    shared
+        .into_iter()
+        .map(|((source, target), (shared, newman))| {
+            let (source_degree, target_degree) = (degree[&source], degree[&target]);
+            ProjectedEdge {
+                source,
+                target,
+                shared,
+                weight: match normalization {
+                    Normalization::None => shared as f64,
+                    Normalization::Jaccard => {
+                        shared as f64 / (source_degree + target_degree - shared) as f64
+                    }
+                    Normalization::Cosine => {
+                        shared as f64 / ((source_degree * target_degree) as f64).sqrt()
+                    }
+                    Normalization::Newman => newman,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Projects the bipartite user to pull request (or repository) graph onto a weighted user to
+/// user co-participation graph. Two users are connected if they participated in at least one of
+/// the same pull requests (or repositories).
+pub fn co_participation(
+    graph: &ContributorGraph,
+    shared_by: SharedBy,
+    normalization: Normalization,
+) -> Vec<ProjectedEdge> {
+    project(&participants(graph, shared_by), normalization)
+}
+
+/// Projects the contributor graph onto a weighted repository to repository graph. Two
+/// repositories are connected if at least one user participated in both.
+pub fn shared_contributors(
+    graph: &ContributorGraph,
+    normalization: Normalization,
+) -> Vec<ProjectedEdge> {
+    // Invert repository -> users into user -> repositories.
+    let mut repositories: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for (repository, users) in participants(graph, SharedBy::Repositories) {
+        for user in users {
+            repositories.entry(user).or_default().insert(repository);
+        }
+    }
+    project(&repositories, normalization)
+}
@@ -0,0 +1,243 @@
+#![warn(clippy::all)]
+use super::{
+    contributor_graph::{ContributorGraph, EdgeKind, VertexKind},
+    projection::{co_participation, Normalization, SharedBy},
+};
+use crate::{
+    error::{Error, ErrorKind, Result},
+    query_structs::repoview_nodes::RepoViewNode,
+};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+/// How the nodes are split up over time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Window {
+    Monthly,
+    Quarterly,
+    /// Windows of length days that start every step days. Windows overlap if step < length.
+    Days {
+        length: i64,
+        step: i64,
+    },
+}
+
+impl FromStr for Window {
+    type Err = Error;
+
+    // Parses monthly, quarterly, days:N, or days:N:STEP.
+    fn from_str(window: &str) -> Result<Self> {
+        let bad_window = || {
+            Error::new(
+                format!(
+                    "Unknown window ({}); expected monthly, quarterly, days:N, or days:N:STEP",
+                    window
+                ),
+                ErrorKind::BadArgs,
+            )
+        };
+
+        let mut parts = window.split(':');
+        match parts.next().map(str::to_lowercase).as_deref() {
+            Some("monthly") => Ok(Window::Monthly),
+            Some("quarterly") => Ok(Window::Quarterly),
+            Some("days") => {
+                let length: i64 = parts
+                    .next()
+                    .and_then(|length| length.parse().ok())
+                    .ok_or_else(bad_window)?;
+                let step: i64 = match parts.next() {
+                    Some(step) => step.parse().map_err(|_| bad_window())?,
+                    None => length,
+                };
+                if length > 0 && step > 0 {
+                    Ok(Window::Days { length, step })
+                } else {
+                    Err(bad_window())
+                }
+            }
+            _ => Err(bad_window()),
+        }
+    }
+}
+
+/// The nodes that fall within [start, end).
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub label: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub nodes: Vec<RepoViewNode>,
+}
+
+/// Activity within a single window.
+#[derive(Clone, Debug, Serialize)]
+pub struct WindowMetrics {
+    pub window: String,
+    pub start: String,
+    pub end: String,
+    pub pull_requests: usize,
+    pub active_users: usize,
+    /// Users whose first participation falls within this window.
+    pub new_users: usize,
+    pub participation_edges: usize,
+    pub co_participation_edges: usize,
+    /// Density of the co-participation graph.
+    pub density: f64,
+}
+
+// Adds months to the first day of a month. None past the dates chrono can represent.
+fn add_months(start: DateTime<Utc>, months: u32) -> Option<DateTime<Utc>> {
+    let month0 = start.month0() + months;
+    Utc.with_ymd_and_hms(
+        start.year() + (month0 / 12) as i32,
+        month0 % 12 + 1,
+        1,
+        0,
+        0,
+        0,
+    )
+    .single()
+}
+
+// Returns the start of the calendar month or quarter containing date.
+fn calendar_start(date: DateTime<Utc>, window: Window) -> Option<DateTime<Utc>> {
+    let month = match window {
+        Window::Quarterly => date.month0() / 3 * 3 + 1,
+        _ => date.month(),
+    };
+    Utc.with_ymd_and_hms(date.year(), month, 1, 0, 0, 0)
+        .single()
+}
+
+/// Splits nodes into windows by their pull request's creation date. Every window between the
+/// earliest and latest date is returned, including empty ones, so the snapshots form an unbroken
+/// time series.
+pub fn split_windows(nodes: &[RepoViewNode], window: Window) -> Vec<Snapshot> {
+    let (first, last) = match (
+        nodes.iter().map(|node| node.date_created.0).min(),
+        nodes.iter().map(|node| node.date_created.0).max(),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec::new(),
+    };
+
+    // (start, end, label) of every window.
+    let mut bounds = Vec::new();
+    match window {
+        Window::Monthly | Window::Quarterly => {
+            let months = if window == Window::Monthly { 1 } else { 3 };
+            let mut start = calendar_start(first, window);
+            while let Some(window_start) = start.filter(|start| *start <= last) {
+                let end = match add_months(window_start, months) {
+                    Some(end) => end,
+                    None => break,
+                };
+                let label = if window == Window::Monthly {
+                    window_start.format("%Y-%m").to_string()
+                } else {
+                    format!("{}-Q{}", window_start.year(), window_start.month0() / 3 + 1)
+                };
+                bounds.push((window_start, end, label));
+                start = Some(end);
+            }
+        }
+        Window::Days { length, step } => {
+            // Midnight always exists so this only falls back to first in theory.
+            let mut start = first
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .map_or(first, |midnight| Utc.from_utc_datetime(&midnight));
+            while start <= last {
+                let end = start + Duration::days(length);
+                let label = format!(
+                    "{}_{}",
+                    start.format("%Y-%m-%d"),
+                    (end - Duration::days(1)).format("%Y-%m-%d")
+                );
+                bounds.push((start, end, label));
+                start += Duration::days(step);
+            }
+        }
+    }
+
+    bounds
+        .into_iter()
+        .map(|(start, end, label)| Snapshot {
+            label,
+            start,
+            end,
+            nodes: nodes
+                .iter()
+                .filter(|node| node.date_created.0 >= start && node.date_created.0 < end)
+                .cloned()
+                .collect(),
+        })
+        .collect()
+}
+
+/// Counts users, pull requests, and edges in each snapshot.
+pub fn window_metrics(snapshots: &[Snapshot]) -> Vec<WindowMetrics> {
+    // First participation of every user across all snapshots.
+    let mut first_seen: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    for snapshot in snapshots {
+        for node in snapshot.nodes.iter() {
+            let date = node.date_created.0;
+            let seen = first_seen.entry(node.participant.as_str()).or_insert(date);
+            if date < *seen {
+                *seen = date;
+            }
+        }
+    }
+
+    snapshots
+        .iter()
+        .map(|snapshot| {
+            let graph = ContributorGraph::from_nodes(&snapshot.nodes);
+            let count_kind = |kind: VertexKind| {
+                graph
+                    .vertices()
+                    .iter()
+                    .filter(|vertex| vertex.kind == kind)
+                    .count()
+            };
+            let active_users = count_kind(VertexKind::User);
+            let co_participation_edges =
+                co_participation(&graph, SharedBy::PullRequests, Normalization::None).len();
+            let new_users: HashSet<&str> = snapshot
+                .nodes
+                .iter()
+                .filter(|node| {
+                    first_seen
+                        .get(node.participant.as_str())
+                        .is_some_and(|first| *first >= snapshot.start && *first < snapshot.end)
+                })
+                .map(|node| node.participant.as_str())
+                .collect();
+
+            WindowMetrics {
+                window: snapshot.label.clone(),
+                start: snapshot.start.to_rfc3339(),
+                end: snapshot.end.to_rfc3339(),
+                pull_requests: count_kind(VertexKind::PullRequest),
+                active_users,
+                new_users: new_users.len(),
+                participation_edges: graph
+                    .edges()
+                    .iter()
+                    .filter(|edge| edge.kind == EdgeKind::Participated)
+                    .count(),
+                co_participation_edges,
+                density: if active_users > 1 {
+                    2.0 * co_participation_edges as f64 / (active_users * (active_users - 1)) as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,20 @@
+#![warn(clippy::all)]
+/// Escapes the five XML special characters for use in text or attribute values. Control
+/// characters other than tab, line feed, and carriage return aren't allowed in XML 1.0 at all so
+/// they're dropped.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            _ if c < ' ' => (),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,21 +1,67 @@
 #[warn(clippy::all)]
 use futures::future::join_all;
 use graphql_client::QueryBody;
-use log::{error, info};
+use log::{error, info, warn};
 use std::{
-    env::args,
-    fs::{create_dir_all, File},
-    path::Path,
+    collections::{BTreeMap, HashSet},
+    fs::{create_dir_all, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
 };
 
+mod args;
+mod dry_run;
 mod error;
 mod errorkind;
+mod graph;
 mod query_client;
 mod query_structs;
+use args::{
+    parse_args, Args, Command, CommunityArgs, MetricsArgs, OutputFormat, ProjectionArgs,
+    RepositoryArg, SnapshotArgs,
+};
+use dry_run::{estimate, print_report, Budget};
 use error::{Error, Result};
 use errorkind::ErrorKind;
+use graph::{
+    community::{community_members, detect_communities},
+    contributor_graph::{ContributorGraph, EdgeKind, VertexKind},
+    dot::write_dot,
+    edge_list::write_edge_list,
+    gexf::write_gexf,
+    graphml::write_graphml,
+    metrics::compute_metrics,
+    projection::{co_participation, shared_contributors, Normalization, ProjectedEdge, SharedBy},
+    snapshots::{split_windows, window_metrics},
+};
 use query_client::QueryClient;
-use query_structs::{repoview::*, repoview_nodes::RepoViewNode, write_nodes::write_nodes};
+use query_structs::{
+    adoptionview::query_adoption_to_end,
+    adoptionview_nodes::AdoptionViewNode,
+    batchview::{batch_size, query_batches_to_end},
+    commitview::*,
+    commitview_nodes::CommitViewNode,
+    discussionview::query_discussions_to_end,
+    discussionview_nodes::DiscussionViewNode,
+    issueview::*,
+    issueview_nodes::IssueViewNode,
+    profileview::query_profiles,
+    profileview_nodes::UserProfile,
+    ratelimitview::query_rate_limit,
+    read_nodes::read_nodes,
+    repoview::*,
+    repoview_nodes::RepoViewNode,
+    review_nodes::ReviewNode,
+    reviewview::query_reviews_to_end,
+    tables::{TableFormat, Tables},
+    write_csv::{
+        write_issues_csv, write_nodes_csv, write_organizations_csv, write_profiles_csv,
+        write_records_csv, OrganizationLayout,
+    },
+    write_nodes::{write_nodes, write_nodes_jsonl},
+    write_parquet::{write_nodes_parquet, write_tables_parquet},
+    write_sqlite::write_sqlite,
+};
 
 // I set NUM_NODES to a reasonable default rather than taking arguments. The API throws an error if
 // the caller may possibly request more than 500,000 nodes at a time. I would either have to
@@ -23,93 +69,284 @@ use query_structs::{repoview::*, repoview_nodes::RepoViewNode, write_nodes::writ
 // return errors to the user (which is probably just myself to be honest).
 const NUM_NODES: i64 = 50;
 
-// Figure out lifetimes later instead of creating Strings
-struct RepositoryArg {
-    owner: String,
-    name: String,
+// Convenience function to make a Vector of requests.
+fn make_requests(repositories: &[RepositoryArg]) -> Vec<QueryBody<repo_view::Variables>> {
+    repositories
+        .iter()
+        .map(|repository| repoview_request(&repository.owner, &repository.name, NUM_NODES, None))
+        .collect()
 }
 
-// Parses command line arguments. This program only takes repository names.
-fn parse_args() -> Result<Vec<RepositoryArg>> {
-    if args().len() < 2 {
-        Err(Error::new("No arguments found", ErrorKind::BadArgs))
+// Creates (or opens for appending) the file at path along with any missing directories.
+fn create_output(path: &Path, append: bool) -> Result<File> {
+    // Create the full directory path if required or return an error with the
+    // failed path.
+    create_dir_all(&path.parent().ok_or_else(|| {
+        // Manually convert NoneError into an Error.
+        std::io::Error::new(
+            std::io::ErrorKind::Other,
+            path.to_str().unwrap_or_else(|| "").to_owned(),
+        )
+    })?)?;
+    // JSON Lines output may be appended to across incremental runs.
+    if append {
+        Ok(OpenOptions::new().create(true).append(true).open(path)?)
     } else {
-        args()
-            .skip(1)
-            .map(|arg| {
-                let mut repo = arg.split('/');
-                Ok(RepositoryArg {
-                    owner: repo
-                        .next()
-                        .ok_or_else(|| {
-                            Error::new(format!("Parsing repository ({})", arg), ErrorKind::BadArgs)
-                        })?
-                        .to_owned(),
-                    name: repo
-                        .next()
-                        .ok_or_else(|| {
-                            Error::new(format!("Parsing repository ({})", arg), ErrorKind::BadArgs)
-                        })?
-                        .to_owned(),
-                })
-            })
-            .collect()
+        Ok(File::create(&path)?)
     }
 }
 
-// Convenience function to make a Vector of requests.
-fn make_requests() -> Result<Vec<QueryBody<repo_view::Variables>>> {
-    Ok(parse_args()?
-        .into_iter()
-        .map(|repository| repoview_request(repository.owner, repository.name, NUM_NODES, None))
-        .collect())
+// Opens output/owner/repo{suffix}.{extension}.
+fn open_output(repository: &str, suffix: &str, extension: &str, append: bool) -> Result<File> {
+    // Paths are a zero cost conversion so we need a variable.
+    let temp_path = format!("output/{}{}.{}", repository, suffix, extension);
+    create_output(Path::new(&temp_path), append)
 }
 
-fn write_output(results: &Vec<Vec<RepoViewNode>>) {
-    // Open a set of output files with the paths output/owner/repo.json.
-    // We'll attempt to write the data regardless of any errors rather than simply failing.
-    let files: Vec<Result<File>> = results
-        .iter()
-        .map(|nodes| {
-            nodes
-                .iter()
-                // Peek to look at the first node to get the repository name.
-                .peekable()
-                .peek()
-                // Report if the Vector is empty then continue.
-                .ok_or_else(|| {
-                    Error::new(
-                        "Empty input data while writing output JSON.",
-                        ErrorKind::EmptyData,
-                    )
-                })
-                .and_then(|node| {
-                    // Paths are a zero cost conversion so we need a variable.
-                    let temp_path = format!("output/{}.json", &node.repository);
-                    let repo_path = Path::new(&temp_path);
-                    // Create the full directory path if required or return an error with the
-                    // failed path.
-                    create_dir_all(&repo_path.parent().ok_or_else(|| {
-                        // Manually convert NoneError into an Error.
-                        std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            repo_path.to_str().unwrap_or_else(|| "").to_owned(),
-                        )
-                    })?)?;
-                    Ok(File::create(&repo_path)?)
-                })
-        })
-        .collect();
+// Everything scraped besides the pull request participants. Each is left empty unless its flag
+// was given except for profiles, which are always fetched.
+#[derive(Default)]
+struct Interactions {
+    profiles: Vec<UserProfile>,
+    issues: Vec<IssueViewNode>,
+    reviews: Vec<ReviewNode>,
+    commits: Vec<CommitViewNode>,
+    adoption: Vec<AdoptionViewNode>,
+    discussions: Vec<DiscussionViewNode>,
+}
+
+// Splits the pull request nodes and interactions of every repository apart so that each
+// repository is written to its own files. Profiles are shared between repositories so each one
+// gets the profiles of its pull request and issue participants.
+fn split_repositories(
+    nodes: Vec<RepoViewNode>,
+    interactions: Interactions,
+) -> BTreeMap<String, (Vec<RepoViewNode>, Interactions)> {
+    let mut repositories: BTreeMap<String, (Vec<RepoViewNode>, Interactions)> = BTreeMap::new();
+    for node in nodes {
+        let entry = repositories.entry(node.repository.clone()).or_default();
+        entry.0.push(node);
+    }
+    for issue in interactions.issues {
+        let entry = repositories.entry(issue.repository.clone()).or_default();
+        entry.1.issues.push(issue);
+    }
+    for review in interactions.reviews {
+        let entry = repositories.entry(review.repository.clone()).or_default();
+        entry.1.reviews.push(review);
+    }
+    for commit in interactions.commits {
+        let entry = repositories.entry(commit.repository.clone()).or_default();
+        entry.1.commits.push(commit);
+    }
+    for adopter in interactions.adoption {
+        let entry = repositories.entry(adopter.repository.clone()).or_default();
+        entry.1.adoption.push(adopter);
+    }
+    for discussion in interactions.discussions {
+        let entry = repositories
+            .entry(discussion.repository.clone())
+            .or_default();
+        entry.1.discussions.push(discussion);
+    }
+
+    for (nodes, split) in repositories.values_mut() {
+        let logins: HashSet<&str> = nodes
+            .iter()
+            .map(|node| node.participant.as_str())
+            .chain(split.issues.iter().map(|issue| issue.participant.as_str()))
+            .collect();
+        split.profiles = interactions
+            .profiles
+            .iter()
+            .filter(|profile| logins.contains(profile.login.as_str()))
+            .cloned()
+            .collect();
+    }
+    repositories
+}
+
+// Builds the contributor graph of a repository's pull requests along with the other
+// interactions.
+fn contributor_graph(nodes: &[RepoViewNode], interactions: &Interactions) -> ContributorGraph {
+    let mut graph = ContributorGraph::from_nodes(nodes);
+    for issue in interactions.issues.iter() {
+        graph.add_issue_node(issue);
+    }
+    for review in interactions.reviews.iter() {
+        graph.add_review_node(review);
+    }
+    for commit in interactions.commits.iter() {
+        graph.add_commit_node(commit);
+    }
+    for adopter in interactions.adoption.iter() {
+        graph.add_adoption_node(adopter);
+    }
+    for discussion in interactions.discussions.iter() {
+        graph.add_discussion_node(discussion);
+    }
+    graph
+}
+
+// Writes a single repository's nodes in the requested format. Interactions are merged into the
+// graph formats and written alongside the nodes as output/owner/repo_issues (and so on)
+// otherwise.
+fn write_repository(
+    repository: &str,
+    nodes: &[RepoViewNode],
+    interactions: &Interactions,
+    args: &Args,
+) -> Result<()> {
+    // Tables and databases are written elsewhere so the file is only opened when needed.
+    let writer = || -> Result<BufWriter<File>> {
+        Ok(BufWriter::new(open_output(
+            repository,
+            "",
+            args.format.extension(),
+            args.append,
+        )?))
+    };
+    let suffixed_writer = |suffix: &str| -> Result<BufWriter<File>> {
+        Ok(BufWriter::new(open_output(
+            repository,
+            suffix,
+            args.format.extension(),
+            args.append,
+        )?))
+    };
+
+    match args.format {
+        OutputFormat::Json => {
+            write_nodes(writer()?, nodes)?;
+            write_nodes(suffixed_writer("_profiles")?, &interactions.profiles)?;
+            if args.issues {
+                write_nodes(suffixed_writer("_issues")?, &interactions.issues)?;
+            }
+            if args.reviews {
+                write_nodes(suffixed_writer("_reviews")?, &interactions.reviews)?;
+            }
+            if args.commits {
+                write_nodes(suffixed_writer("_commits")?, &interactions.commits)?;
+            }
+            if args.adoption {
+                write_nodes(suffixed_writer("_adoption")?, &interactions.adoption)?;
+            }
+            if args.discussions {
+                write_nodes(suffixed_writer("_discussions")?, &interactions.discussions)?;
+            }
+            Ok(())
+        }
+        OutputFormat::JsonLines => {
+            write_nodes_jsonl(writer()?, nodes)?;
+            write_nodes_jsonl(suffixed_writer("_profiles")?, &interactions.profiles)?;
+            if args.issues {
+                write_nodes_jsonl(suffixed_writer("_issues")?, &interactions.issues)?;
+            }
+            if args.reviews {
+                write_nodes_jsonl(suffixed_writer("_reviews")?, &interactions.reviews)?;
+            }
+            if args.commits {
+                write_nodes_jsonl(suffixed_writer("_commits")?, &interactions.commits)?;
+            }
+            if args.adoption {
+                write_nodes_jsonl(suffixed_writer("_adoption")?, &interactions.adoption)?;
+            }
+            if args.discussions {
+                write_nodes_jsonl(suffixed_writer("_discussions")?, &interactions.discussions)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv => {
+            write_nodes_csv(writer()?, nodes, args.org_layout)?;
+            if args.org_layout == OrganizationLayout::Long {
+                let org_writer =
+                    BufWriter::new(open_output(repository, "_organizations", "csv", false)?);
+                write_organizations_csv(org_writer, nodes)?;
+            }
+            write_profiles_csv(suffixed_writer("_profiles")?, &interactions.profiles)?;
+            if args.issues {
+                write_issues_csv(suffixed_writer("_issues")?, &interactions.issues)?;
+            }
+            if args.reviews {
+                write_records_csv(suffixed_writer("_reviews")?, &interactions.reviews)?;
+            }
+            if args.commits {
+                write_records_csv(suffixed_writer("_commits")?, &interactions.commits)?;
+            }
+            if args.adoption {
+                write_records_csv(suffixed_writer("_adoption")?, &interactions.adoption)?;
+            }
+            if args.discussions {
+                write_records_csv(suffixed_writer("_discussions")?, &interactions.discussions)?;
+            }
+            Ok(())
+        }
+        OutputFormat::GraphMl => write_graphml(writer()?, &contributor_graph(nodes, interactions)),
+        OutputFormat::Gexf => write_gexf(writer()?, &contributor_graph(nodes, interactions)),
+        OutputFormat::Dot => {
+            let graph = contributor_graph(nodes, interactions);
+            write_dot(writer()?, &graph, &graph, &args.dot)
+        }
+        OutputFormat::Parquet => write_nodes_parquet(writer()?, nodes),
+        OutputFormat::Tables => write_tables(repository, nodes, args.table_format),
+        OutputFormat::Sqlite => {
+            if let Some(parent) = args.database.parent() {
+                create_dir_all(parent)?;
+            }
+            info!("Merging {} into {}", repository, args.database.display());
+            write_sqlite(&args.database, &Tables::from_nodes(nodes))
+        }
+    }
+}
+
+// Writes a repository's normalized tables to output/owner/repo/{table}.{csv,parquet}.
+fn write_tables(repository: &str, nodes: &[RepoViewNode], format: TableFormat) -> Result<()> {
+    let tables = Tables::from_nodes(nodes);
+    info!(
+        "Writing {} rows of normalized tables for {}",
+        tables.row_count(),
+        repository
+    );
+    let open_table = |table: &str| -> Result<BufWriter<File>> {
+        Ok(BufWriter::new(open_output(
+            repository,
+            &format!("/{}", table),
+            format.extension(),
+            false,
+        )?))
+    };
 
+    match format {
+        TableFormat::Csv => {
+            write_records_csv(open_table("users")?, &tables.users)?;
+            write_records_csv(open_table("repositories")?, &tables.repositories)?;
+            write_records_csv(open_table("pull_requests")?, &tables.pull_requests)?;
+            write_records_csv(open_table("participations")?, &tables.participations)?;
+            write_records_csv(
+                open_table("user_organizations")?,
+                &tables.user_organizations,
+            )
+        }
+        TableFormat::Parquet => write_tables_parquet(&tables, open_table),
+    }
+}
+
+fn write_output(repositories: &BTreeMap<String, (Vec<RepoViewNode>, Interactions)>, args: &Args) {
+    // Open a set of output files with the paths output/owner/repo.json (or .jsonl/.csv).
+    // We'll attempt to write the data regardless of any errors rather than simply failing.
     // I'm not sure what else to do beyond reporting the errors.
-    for (file_opt, nodes) in files.iter().zip(results.iter()) {
-        match file_opt {
-            Ok(file) => {
-                if let Err(e) = write_nodes(file, &nodes) {
-                    error!("{}", e)
-                }
-            }
-            Err(e) => error!("{}", e),
+    if repositories.is_empty() {
+        error!(
+            "{}",
+            Error::new(
+                "Empty input data while writing output.",
+                ErrorKind::EmptyData,
+            )
+        );
+    }
+    for (repository, (nodes, interactions)) in repositories.iter() {
+        if let Err(e) = write_repository(repository, nodes, interactions, args) {
+            error!("{}", e)
         }
     }
 }
@@ -126,15 +363,236 @@ async fn query_all(
     join_all(futures).await
 }
 
-#[tokio::main]
-async fn main() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let _log =
-        pretty_env_logger::try_init().map_err(|e| eprintln!("Failed to initialize logger: {}", e));
+/// Same as query_all but pages through issues.
+async fn query_all_issues(
+    client: &QueryClient,
+    repositories: &[RepositoryArg],
+) -> Vec<Result<Vec<issue_view::ResponseData>>> {
+    let futures: Vec<_> = repositories
+        .iter()
+        .map(|repository| {
+            query_issues_to_end(client, &repository.owner, &repository.name, NUM_NODES)
+        })
+        .collect();
+    join_all(futures).await
+}
 
-    let requests = make_requests()?;
-    let client = QueryClient::new()?;
-    info!("Beginning scrape.");
-    let (responses_nested, errors) = query_all(&client, &requests)
+/// Same as query_all but pages through commits.
+async fn query_all_commits(
+    client: &QueryClient,
+    repositories: &[RepositoryArg],
+) -> Vec<Result<Vec<commit_view::ResponseData>>> {
+    let futures: Vec<_> = repositories
+        .iter()
+        .map(|repository| {
+            query_commits_to_end(client, &repository.owner, &repository.name, NUM_NODES)
+        })
+        .collect();
+    join_all(futures).await
+}
+
+// Loads and concatenates previously scraped nodes from each input file.
+fn load_nodes(inputs: &[PathBuf]) -> Result<Vec<RepoViewNode>> {
+    let mut nodes = Vec::new();
+    for input in inputs {
+        nodes.extend(read_nodes::<RepoViewNode, _>(input)?);
+    }
+    Ok(nodes)
+}
+
+// Writes a projection as a weighted edge list or through the graph writers.
+fn write_projection(
+    args: &ProjectionArgs,
+    name: &str,
+    graph: &ContributorGraph,
+    kinds: (VertexKind, EdgeKind),
+    edges: &[ProjectedEdge],
+) -> Result<()> {
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("output/{}.{}", name, args.format.extension())));
+    info!("Writing {} edges to {}", edges.len(), path.display());
+    let writer = BufWriter::new(create_output(&path, false)?);
+
+    let (vertex_kind, edge_kind) = kinds;
+    match args.format {
+        OutputFormat::Csv => write_edge_list(writer, graph, edges),
+        OutputFormat::GraphMl => {
+            write_graphml(writer, &graph.projection(vertex_kind, edge_kind, edges))
+        }
+        OutputFormat::Gexf => write_gexf(writer, &graph.projection(vertex_kind, edge_kind, edges)),
+        OutputFormat::Dot => write_dot(
+            writer,
+            &graph.projection(vertex_kind, edge_kind, edges),
+            graph,
+            &args.dot,
+        ),
+        OutputFormat::Json
+        | OutputFormat::JsonLines
+        | OutputFormat::Tables
+        | OutputFormat::Sqlite
+        | OutputFormat::Parquet => Err(Error::new(
+            "Projections can't be written as nodes",
+            ErrorKind::BadArgs,
+        )),
+    }
+}
+
+// Writes the weighted user to user co-participation graph.
+fn run_co_participation(args: &ProjectionArgs) -> Result<()> {
+    let graph = ContributorGraph::from_nodes(&load_nodes(&args.inputs)?);
+    let edges = co_participation(&graph, args.shared_by, args.normalization);
+    write_projection(
+        args,
+        "coparticipation",
+        &graph,
+        (VertexKind::User, EdgeKind::CoParticipated),
+        &edges,
+    )
+}
+
+// Writes the weighted repository to repository shared contributor graph.
+fn run_repository_similarity(args: &ProjectionArgs) -> Result<()> {
+    let graph = ContributorGraph::from_nodes(&load_nodes(&args.inputs)?);
+    let edges = shared_contributors(&graph, args.normalization);
+    write_projection(
+        args,
+        "reposimilarity",
+        &graph,
+        (VertexKind::Repository, EdgeKind::SharedContributors),
+        &edges,
+    )
+}
+
+// Writes per user centrality measures along with a summary of the whole graph.
+fn run_metrics(args: &MetricsArgs) -> Result<()> {
+    let graph = ContributorGraph::from_nodes(&load_nodes(&args.inputs)?);
+    // Metrics are measured on the user co-participation graph. Paths in the full graph alternate
+    // between users and pull requests and it has no triangles, which makes centrality and
+    // clustering meaningless for users.
+    let edges = co_participation(&graph, SharedBy::PullRequests, Normalization::None);
+    let users = graph.projection(VertexKind::User, EdgeKind::CoParticipated, &edges);
+    let (metrics, summary) = compute_metrics(&users);
+    info!(
+        "{} users, {} edges, density {:.4}, {} components (largest: {})",
+        summary.vertices,
+        summary.edges,
+        summary.density,
+        summary.components,
+        summary.component_sizes.first().copied().unwrap_or(0)
+    );
+
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("output/metrics.{}", args.format.extension())));
+    let writer = BufWriter::new(create_output(&path, false)?);
+    match args.format {
+        OutputFormat::Json => write_nodes(writer, &metrics)?,
+        _ => write_records_csv(writer, &metrics)?,
+    }
+
+    let mut summary_writer = BufWriter::new(create_output(&args.summary, false)?);
+    serde_json::to_writer_pretty(&mut summary_writer, &summary)?;
+    Ok(summary_writer.flush()?)
+}
+
+// Detects communities in the co-participation graph and compares them against the users'
+// companies and organizations.
+fn run_communities(args: &CommunityArgs) -> Result<()> {
+    let graph = ContributorGraph::from_nodes(&load_nodes(&args.inputs)?);
+    let (users, report) = detect_communities(&graph);
+    info!(
+        "Found {} communities with modularity {:.4} (company: {:.4}, organization: {:.4})",
+        report.communities.len(),
+        report.modularity,
+        report.company_modularity,
+        report.organization_modularity
+    );
+
+    let path = args.output.clone().unwrap_or_else(|| {
+        PathBuf::from(format!("output/communities.{}", args.format.extension()))
+    });
+    let writer = BufWriter::new(create_output(&path, false)?);
+    match args.format {
+        OutputFormat::Csv => write_records_csv(writer, &community_members(&users)),
+        OutputFormat::Json => write_nodes(writer, &community_members(&users)),
+        OutputFormat::GraphMl => write_graphml(writer, &users),
+        OutputFormat::Gexf => write_gexf(writer, &users),
+        OutputFormat::Dot => write_dot(writer, &users, &graph, &args.dot),
+        OutputFormat::JsonLines
+        | OutputFormat::Tables
+        | OutputFormat::Sqlite
+        | OutputFormat::Parquet => Err(Error::new(
+            "Communities are written as csv, json, graphml, gexf, or dot",
+            ErrorKind::BadArgs,
+        )),
+    }?;
+
+    let mut report_writer = BufWriter::new(create_output(&args.report, false)?);
+    serde_json::to_writer_pretty(&mut report_writer, &report)?;
+    Ok(report_writer.flush()?)
+}
+
+// Writes one graph per time window along with a CSV of each window's activity.
+fn run_snapshots(args: &SnapshotArgs) -> Result<()> {
+    let snapshots = split_windows(&load_nodes(&args.inputs)?, args.window);
+    info!(
+        "Writing {} snapshots to {}",
+        snapshots.len(),
+        args.output_dir.display()
+    );
+
+    for snapshot in snapshots.iter() {
+        let path = args
+            .output_dir
+            .join(format!("{}.{}", snapshot.label, args.format.extension()));
+        let writer = BufWriter::new(create_output(&path, false)?);
+        let graph = ContributorGraph::from_nodes(&snapshot.nodes);
+        match args.format {
+            OutputFormat::GraphMl => write_graphml(writer, &graph),
+            OutputFormat::Gexf => write_gexf(writer, &graph),
+            OutputFormat::Dot => write_dot(writer, &graph, &graph, &args.dot),
+            OutputFormat::Json
+            | OutputFormat::JsonLines
+            | OutputFormat::Csv
+            | OutputFormat::Tables
+            | OutputFormat::Sqlite
+            | OutputFormat::Parquet => Err(Error::new(
+                "Snapshots are written as graphml, gexf, or dot",
+                ErrorKind::BadArgs,
+            )),
+        }?;
+    }
+
+    let metrics_writer = BufWriter::new(create_output(&args.metrics, false)?);
+    write_records_csv(metrics_writer, &window_metrics(&snapshots))
+}
+
+// Pages through every repository's pull requests. Repositories share requests unless a single
+// repository's query is already too large to share one.
+async fn query_pull_requests(
+    client: &QueryClient,
+    repositories: &[RepositoryArg],
+) -> Vec<repo_view::ResponseData> {
+    let batched = batch_size(NUM_NODES).unwrap_or_else(|e| {
+        error!("{}; querying repositories one at a time", e);
+        1
+    }) > 1;
+    if batched {
+        let names: Vec<(&str, &str)> = repositories
+            .iter()
+            .map(|repository| (repository.owner.as_str(), repository.name.as_str()))
+            .collect();
+        match query_batches_to_end(client, &names, NUM_NODES).await {
+            Ok(responses) => return responses,
+            Err(e) => error!("{}; querying repositories one at a time", e),
+        }
+    }
+
+    let requests = make_requests(repositories);
+    let (responses_nested, errors) = query_all(client, &requests)
         .await
         .into_iter()
         .partition::<Vec<_>, _>(Result::is_ok);
@@ -143,14 +601,151 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sy
         // Partitioned into ok/err so we can unwrap the error here.
         error!("Error returned during query phase: {}", error.unwrap_err());
     }
+    responses_nested.into_iter().flatten().flatten().collect()
+}
+
+// Reports what a scrape would cost without fetching anything. The token's remaining budget is
+// looked up with a dry run that doesn't count against it.
+async fn dry_run(args: &Args) -> Result<()> {
+    // Estimates are still useful without a token or a connection.
+    let rate_limit = match QueryClient::new() {
+        Ok(client) => query_rate_limit(&client).await,
+        Err(e) => Err(e),
+    };
+    let budget = match rate_limit {
+        Ok(rate_limit) => rate_limit.map_or_else(Budget::assumed, Budget::from),
+        Err(e) => {
+            warn!("{}; assuming a full rate limit", e);
+            Budget::assumed()
+        }
+    };
+    print_report(&estimate(args, NUM_NODES)?, &budget);
+    Ok(())
+}
+
+async fn scrape(args: &Args) -> Result<()> {
+    let client = QueryClient::new()?;
+    info!("Beginning scrape.");
+    let responses = query_pull_requests(&client, &args.repositories).await;
 
     info!("Parsing nodes.");
-    let responses = responses_nested.into_iter().flatten().flatten().collect();
     //info!("Size: {}", responses.len());
-    let parsed_data = RepoViewNode::parse_nodes(&responses);
+    let mut parsed_data = RepoViewNode::parse_nodes(&responses);
+    let mut participant_ids = RepoViewNode::participant_ids(&responses);
+    let mut interactions = Interactions::default();
+
+    if args.reviews {
+        info!("Scraping reviews.");
+        let futures: Vec<_> = args
+            .repositories
+            .iter()
+            .map(|repository| {
+                query_reviews_to_end(&client, &repository.owner, &repository.name, NUM_NODES)
+            })
+            .collect();
+        for result in join_all(futures).await {
+            match result {
+                Ok(responses) => interactions
+                    .reviews
+                    .extend(ReviewNode::parse_nodes(&responses)),
+                Err(e) => error!("Error returned while querying reviews: {}", e),
+            }
+        }
+    }
+
+    if args.issues {
+        info!("Scraping issues.");
+        let mut issue_responses = Vec::new();
+        for result in query_all_issues(&client, &args.repositories).await {
+            match result {
+                Ok(responses) => issue_responses.extend(responses),
+                Err(e) => error!("Error returned while querying issues: {}", e),
+            }
+        }
+        participant_ids.extend(IssueViewNode::participant_ids(&issue_responses));
+        interactions.issues = IssueViewNode::parse_nodes(&issue_responses);
+    }
+
+    if args.commits {
+        info!("Scraping commits.");
+        let mut commit_responses = Vec::new();
+        for result in query_all_commits(&client, &args.repositories).await {
+            match result {
+                Ok(responses) => commit_responses.extend(responses),
+                Err(e) => error!("Error returned while querying commits: {}", e),
+            }
+        }
+        interactions.commits = CommitViewNode::parse_nodes(&commit_responses);
+    }
+
+    if args.adoption {
+        info!("Scraping stargazers, watchers, and forks.");
+        let futures: Vec<_> = args
+            .repositories
+            .iter()
+            .map(|repository| {
+                query_adoption_to_end(&client, &repository.owner, &repository.name, NUM_NODES)
+            })
+            .collect();
+        for responses in join_all(futures).await {
+            interactions
+                .adoption
+                .extend(AdoptionViewNode::parse_nodes(&responses));
+        }
+    }
+
+    if args.discussions {
+        info!("Scraping discussions.");
+        let futures: Vec<_> = args
+            .repositories
+            .iter()
+            .map(|repository| {
+                query_discussions_to_end(&client, &repository.owner, &repository.name, NUM_NODES)
+            })
+            .collect();
+        for result in join_all(futures).await {
+            match result {
+                Ok(responses) => interactions
+                    .discussions
+                    .extend(DiscussionViewNode::parse_nodes(&responses)),
+                Err(e) => error!("Error returned while querying discussions: {}", e),
+            }
+        }
+    }
+
+    // Profiles are fetched once per user rather than inside every pull request and issue they
+    // took part in.
+    let users: Vec<(String, String)> = participant_ids.into_iter().collect();
+    let responses = query_profiles(&client, &users, NUM_NODES).await;
+    interactions.profiles = UserProfile::parse_nodes(&responses);
+    let profiles = UserProfile::by_login(&interactions.profiles);
+    for node in parsed_data.iter_mut() {
+        node.fill_profile(&profiles);
+    }
+    for issue in interactions.issues.iter_mut() {
+        issue.fill_profile(&profiles);
+    }
+
     info!("Writing files.");
-    // I think I flattened everything too much?
-    write_output(&vec![parsed_data]);
+    write_output(&split_repositories(parsed_data, interactions), args);
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _log =
+        pretty_env_logger::try_init().map_err(|e| eprintln!("Failed to initialize logger: {}", e));
+
+    match parse_args()? {
+        Command::Scrape(args) if args.dry_run => dry_run(&args).await?,
+        Command::Scrape(args) => scrape(&args).await?,
+        Command::CoParticipation(args) => run_co_participation(&args)?,
+        Command::RepositorySimilarity(args) => run_repository_similarity(&args)?,
+        Command::Metrics(args) => run_metrics(&args)?,
+        Command::Communities(args) => run_communities(&args)?,
+        Command::Snapshots(args) => run_snapshots(&args)?,
+    }
 
     Ok(())
 }
@@ -1,7 +1,7 @@
 #[warn(clippy::all)]
 use graphql_client::{GraphQLQuery, Response};
 use reqwest::Client;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use std::{thread::sleep, time::Duration};
 
 use crate::{
@@ -9,7 +9,8 @@ use crate::{
     query_structs::backoff_timer::BackoffTimer,
 };
 
-const DEFAULT_TIMEOUT: u64 = 10;
+// Seconds to pause after each request unless the backoff timer says otherwise.
+pub const DEFAULT_TIMEOUT: u64 = 10;
 const GITHUBAPI: &str = "https://api.github.com/graphql";
 // User agents are always required for the GitHub API.
 const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), " (", env!("CARGO_PKG_VERSION"), ")");
@@ -37,10 +38,22 @@ impl QueryClient {
     where
         Q: Serialize,
         R: BackoffTimer<R> + GraphQLQuery + Send + Sync + Unpin,
+    {
+        self.request_with(query, R::backoff).await
+    }
+
+    /// Same as request but for documents built at runtime (such as batches of repositories) that
+    /// don't have a GraphQLQuery. The response is deserialized into D and backoff plays the part
+    /// of BackoffTimer::backoff.
+    pub async fn request_with<Q, D, F>(&self, query: &Q, backoff: F) -> Result<Response<D>>
+    where
+        Q: Serialize,
+        D: DeserializeOwned,
+        F: Fn(&D) -> Option<Duration>,
     {
         // The block below queries the GitHub API using the associated token and query. I'm saving
-        // the result into a variable to query R::backoff().
-        let result: Result<Response<R::ResponseData>> = self
+        // the result into a variable to query the backoff timer.
+        let result: Result<Response<D>> = self
             .client
             .post(GITHUBAPI)
             .bearer_auth(&self.token)
@@ -56,20 +69,20 @@ impl QueryClient {
                 )
             });
 
-        QueryClient::backoff::<R>(&result).await;
+        QueryClient::backoff(&result, backoff).await;
         result
     }
 
-    // The backoff function defers to R::backoff for the timer. If the implementer does not use the
-    // rate limit info but returns some other amount of time we still defer to their wisdom.
-    // Likewise, if the implementer returns None we simply use a default.
-    async fn backoff<R>(response: &Result<Response<R::ResponseData>>)
+    // The backoff function defers to the backoff timer. If the implementer does not use the rate
+    // limit info but returns some other amount of time we still defer to their wisdom. Likewise,
+    // if the implementer returns None we simply use a default.
+    async fn backoff<D, F>(response: &Result<Response<D>>, backoff: F)
     where
-        R: BackoffTimer<R> + GraphQLQuery + Send + Sync + Unpin,
+        F: Fn(&D) -> Option<Duration>,
     {
         match response {
             Ok(ref repdata) if repdata.data.is_some() => sleep(
-                R::backoff(&repdata.data.as_ref().unwrap())
+                backoff(&repdata.data.as_ref().unwrap())
                     .unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT)),
             ),
             _ => sleep(Duration::from_secs(DEFAULT_TIMEOUT)),
@@ -0,0 +1,177 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::{page_to_end, Cursor},
+    scalars::DateTime,
+};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::{error, info};
+use std::time::Duration;
+
+// Stargazers, watchers, and forks are separate connections that end at different times so each
+// gets its own query rather than juggling three cursors in one.
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/stargazerquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct StargazerView;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/watcherquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct WatcherView;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/forkquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct ForkView;
+
+/// Every page of the three adoption queries for a single repository.
+#[derive(Clone, Debug, Default)]
+pub struct AdoptionResponses {
+    pub stargazers: Vec<stargazer_view::ResponseData>,
+    pub watchers: Vec<watcher_view::ResponseData>,
+    pub forks: Vec<fork_view::ResponseData>,
+}
+
+impl Cursor<StargazerView> for StargazerView {
+    fn cursor(response: &stargazer_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.stargazers.page_info.has_next_page => repo_data
+                .stargazers
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<StargazerView> for StargazerView {
+    fn backoff(response: &stargazer_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+impl Cursor<WatcherView> for WatcherView {
+    fn cursor(response: &watcher_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.watchers.page_info.has_next_page => repo_data
+                .watchers
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<WatcherView> for WatcherView {
+    fn backoff(response: &watcher_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+impl Cursor<ForkView> for ForkView {
+    fn cursor(response: &fork_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.forks.page_info.has_next_page => repo_data
+                .forks
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<ForkView> for ForkView {
+    fn backoff(response: &fork_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+// Logs a relationship that failed to page so the others are still returned.
+fn or_log<T>(result: GGGResult<Vec<T>>, relationship: &str, owner: &str, name: &str) -> Vec<T> {
+    result.unwrap_or_else(|e| {
+        error!(
+            "Error returned while querying the {} of {}/{}: {}",
+            relationship, owner, name, e
+        );
+        Vec::new()
+    })
+}
+
+/// Pages through the stargazers, watchers, and forks of owner/name one after another. A
+/// relationship that fails is logged and left empty so the others are still returned.
+pub async fn query_adoption_to_end(
+    client: &QueryClient,
+    owner: &str,
+    name: &str,
+    nnodes: i64,
+) -> AdoptionResponses {
+    info!(
+        "Scraping stargazers, watchers, and forks from {}/{}",
+        owner, name
+    );
+
+    let stargazers = page_to_end::<StargazerView, _>(client, |stargazercursor| {
+        StargazerView::build_query(stargazer_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            stargazercursor,
+        })
+    })
+    .await;
+    let stargazers = or_log(stargazers, "stargazers", owner, name);
+    let watchers = page_to_end::<WatcherView, _>(client, |watchercursor| {
+        WatcherView::build_query(watcher_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            watchercursor,
+        })
+    })
+    .await;
+    let watchers = or_log(watchers, "watchers", owner, name);
+    let forks = page_to_end::<ForkView, _>(client, |forkcursor| {
+        ForkView::build_query(fork_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            forkcursor,
+        })
+    })
+    .await;
+    let forks = or_log(forks, "forks", owner, name);
+
+    AdoptionResponses {
+        stargazers,
+        watchers,
+        forks,
+    }
+}
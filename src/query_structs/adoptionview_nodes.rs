@@ -0,0 +1,86 @@
+#![warn(clippy::all)]
+use super::{adoptionview::*, scalars::DateTime};
+use serde::{Deserialize, Serialize};
+
+/// How a user adopted a repository.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Adoption {
+    Star,
+    Watch,
+    Fork,
+}
+
+/// A user that starred, watched, or forked a repository.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct AdoptionViewNode {
+    pub repository: String,
+    /// Login of the stargazer or watcher or of the fork's owner (which may be an organization).
+    pub user: String,
+    pub adoption: Adoption,
+    /// When the repository was starred or forked. GitHub doesn't record when users started
+    /// watching.
+    pub date: Option<DateTime>,
+    /// The fork's owner/name.
+    pub fork: Option<String>,
+}
+
+impl AdoptionViewNode {
+    fn new(repository: &str, user: &str, adoption: Adoption) -> Self {
+        AdoptionViewNode {
+            repository: repository.to_owned(),
+            user: user.to_owned(),
+            adoption,
+            date: None,
+            fork: None,
+        }
+    }
+
+    /// Flattens the stargazers, watchers, and forks into AdoptionViewNodes in that order.
+    pub fn parse_nodes(data: &AdoptionResponses) -> Vec<AdoptionViewNode> {
+        let mut parsed = Vec::new();
+
+        for repo in data
+            .stargazers
+            .iter()
+            .filter_map(|page| page.repository.as_ref())
+        {
+            let stargazers = repo.stargazers.edges.iter().flatten().flatten();
+            parsed.extend(stargazers.map(|edge| AdoptionViewNode {
+                date: Some(edge.starred_at),
+                ..AdoptionViewNode::new(&repo.name_with_owner, &edge.node.login, Adoption::Star)
+            }));
+        }
+
+        for repo in data
+            .watchers
+            .iter()
+            .filter_map(|page| page.repository.as_ref())
+        {
+            let watchers = repo.watchers.edges.iter().flatten().flatten();
+            parsed.extend(watchers.filter_map(|edge| edge.node.as_ref()).map(|user| {
+                AdoptionViewNode::new(&repo.name_with_owner, &user.login, Adoption::Watch)
+            }));
+        }
+
+        for repo in data
+            .forks
+            .iter()
+            .filter_map(|page| page.repository.as_ref())
+        {
+            let forks = repo.forks.edges.iter().flatten().flatten();
+            parsed.extend(forks.filter_map(|edge| edge.node.as_ref()).map(|fork| {
+                AdoptionViewNode {
+                    date: Some(fork.created_at),
+                    fork: Some(fork.name_with_owner.clone()),
+                    ..AdoptionViewNode::new(
+                        &repo.name_with_owner,
+                        &fork.owner.login,
+                        Adoption::Fork,
+                    )
+                }
+            }));
+        }
+        parsed
+    }
+}
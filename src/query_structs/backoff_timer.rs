@@ -1,7 +1,13 @@
 #[warn(clippy::all)]
+use super::scalars::DateTime;
+use chrono::offset::Utc;
 use graphql_client::GraphQLQuery;
+use log::{info, warn};
 use std::time::Duration;
 
+// Default sleep if the time until the rate limit resets can't be calculated.
+const SLEEP_SEC: u64 = 900;
+
 /// Implement by returning a parsed version of the epoch time stamp from RateLimit.
 /// You may return a reasonable default or None if RateLimit isn't available.
 pub trait BackoffTimer<R> {
@@ -9,3 +15,18 @@ pub trait BackoffTimer<R> {
     where
         R: GraphQLQuery + Send + Sync;
 }
+
+/// Sleep time for a RateLimit with remaining queries that resets at reset_at. Every query has
+/// its own RateLimit type so implementations pass the fields along instead.
+pub fn backoff(remaining: i64, reset_at: DateTime) -> Option<Duration> {
+    if remaining == 0 {
+        info!("Rate limit reached. Sleeping until: {}", reset_at);
+        Some((reset_at.0 - Utc::now()).to_std().unwrap_or_else(|e| {
+            warn!("{}", e);
+            Duration::from_secs(SLEEP_SEC)
+        }))
+    } else {
+        info!("Queries remaining before pausing: {}", remaining);
+        None
+    }
+}
@@ -0,0 +1,255 @@
+#![warn(clippy::all)]
+use super::{
+    backoff_timer::BackoffTimer,
+    cursor::Cursor,
+    query_cost::{QueryCost, MAX_NODES},
+    repoview::*,
+};
+use crate::{
+    error::{Error, ErrorKind, Result},
+    query_client::QueryClient,
+};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+
+// Upper bound on repositories per document regardless of cost so that a single slow repository
+// doesn't hold up too many others.
+const MAX_BATCH: usize = 20;
+// The repository field of queries/repoquery.graphql. Batches copy its selection set so that they
+// fetch exactly what RepoView does.
+const REPOSITORY_FIELD: &str = "repository(owner: $owner, name: $name)";
+
+/// A document of several aliased repository(owner:, name:) blocks built at runtime. Serializes
+/// the same way as graphql_client's QueryBody.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchQuery {
+    pub variables: Map<String, Value>,
+    pub query: String,
+    pub operation_name: &'static str,
+}
+
+/// Each alias (r0, r1, ...) maps to the repository it was given.
+#[derive(Debug, Deserialize)]
+pub struct BatchResponse {
+    #[serde(rename = "rateLimit")]
+    pub rate_limit: Option<repo_view::RepoViewRateLimit>,
+    #[serde(flatten)]
+    pub repositories: HashMap<String, Option<repo_view::RepoViewRepository>>,
+}
+
+// A repository that still has pull requests to fetch.
+#[derive(Clone, Debug)]
+struct Pending {
+    owner: String,
+    name: String,
+    pullcursor: Option<String>,
+}
+
+fn alias(index: usize) -> String {
+    format!("r{}", index)
+}
+
+// The repository's selection set in RepoView (from the opening to the closing brace).
+fn repository_selection() -> Result<&'static str> {
+    let start = repo_view::QUERY
+        .find(REPOSITORY_FIELD)
+        .and_then(|field| {
+            repo_view::QUERY[field..]
+                .find('{')
+                .map(|brace| field + brace)
+        })
+        .ok_or_else(|| {
+            Error::new(
+                format!("No {} in repoquery.graphql", REPOSITORY_FIELD),
+                ErrorKind::EmptyData,
+            )
+        })?;
+    let mut depth = 0;
+    for (i, c) in repo_view::QUERY[start..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(&repo_view::QUERY[start..=start + i]);
+                }
+            }
+            _ => (),
+        }
+    }
+    Err(Error::new(
+        "Unbalanced braces in repoquery.graphql",
+        ErrorKind::EmptyData,
+    ))
+}
+
+/// Worst case cost of a single repository's block in a batch.
+pub fn repository_cost(nnodes: i64) -> Result<QueryCost> {
+    Ok(QueryCost::estimate(repository_selection()?, nnodes))
+}
+
+/// How many repositories fit in one document without exceeding GitHub's node limit.
+pub fn batch_size(nnodes: i64) -> Result<usize> {
+    let per_repository = repository_cost(nnodes)?.nodes.max(1);
+    Ok(((MAX_NODES / per_repository) as usize).min(MAX_BATCH))
+}
+
+// Builds a document with an aliased repository block (a copy of selection) per pending
+// repository. Every variable but nnodes is suffixed with the repository's index.
+fn batch_query(batch: &[Pending], selection: &str, nnodes: i64) -> BatchQuery {
+    let mut parameters = vec!["$nnodes: Int!".to_owned()];
+    let mut fields = Vec::new();
+    let mut variables = Map::new();
+    variables.insert("nnodes".to_owned(), nnodes.into());
+
+    for (i, repository) in batch.iter().enumerate() {
+        parameters.push(format!(
+            "$owner{i}: String!, $name{i}: String!, $pullcursor{i}: String",
+            i = i
+        ));
+        fields.push(format!(
+            "  {}: repository(owner: $owner{i}, name: $name{i}) {}",
+            alias(i),
+            selection.replace("$pullcursor", &format!("$pullcursor{}", i)),
+            i = i
+        ));
+        variables.insert(format!("owner{}", i), repository.owner.clone().into());
+        variables.insert(format!("name{}", i), repository.name.clone().into());
+        variables.insert(
+            format!("pullcursor{}", i),
+            repository.pullcursor.clone().into(),
+        );
+    }
+
+    BatchQuery {
+        variables,
+        query: format!(
+            "query RepoBatch({}) {{\n{}\n  rateLimit {{\n    remaining\n    resetAt\n    used\n  }}\n}}\n",
+            parameters.join(", "),
+            fields.join("\n")
+        ),
+        operation_name: "RepoBatch",
+    }
+}
+
+// Reuses RepoView's timer since batches report the same rate limit.
+fn backoff(response: &BatchResponse) -> Option<std::time::Duration> {
+    RepoView::backoff(&repo_view::ResponseData {
+        repository: None,
+        rate_limit: response.rate_limit.clone(),
+    })
+}
+
+// Pages through each repository of a failed batch on its own, picking up from the batch's
+// cursor, so that one bad repository or request doesn't lose the others.
+async fn query_individually(
+    client: &QueryClient,
+    batch: Vec<Pending>,
+    nnodes: i64,
+) -> Vec<repo_view::ResponseData> {
+    let mut responses = Vec::new();
+    for repository in batch {
+        let request = repoview_request(
+            &repository.owner,
+            &repository.name,
+            nnodes,
+            repository.pullcursor.clone(),
+        );
+        match query_to_end(client, &request).await {
+            Ok(pages) => responses.extend(pages),
+            Err(e) => error!(
+                "Error returned while querying {}/{}: {}",
+                repository.owner, repository.name, e
+            ),
+        }
+    }
+    responses
+}
+
+/// Works like query_to_end for many repositories at once. Repositories are packed into as few
+/// documents as GitHub's limits allow and the responses are split back into one RepoView
+/// response per repository and page. Repositories drop out of the batches as they run out of
+/// pull requests. The repositories of a batch that fails are retried one at a time.
+pub async fn query_batches_to_end(
+    client: &QueryClient,
+    repositories: &[(&str, &str)],
+    nnodes: i64,
+) -> Result<Vec<repo_view::ResponseData>> {
+    let selection = repository_selection()?;
+    let per_batch = batch_size(nnodes)?.max(1);
+    let cost = repository_cost(nnodes)?;
+    info!(
+        "Scraping {} repositories, {} per request ({} nodes and {} points each)",
+        repositories.len(),
+        per_batch,
+        cost.nodes,
+        cost.points()
+    );
+
+    let mut responses = Vec::new();
+    let mut pending: VecDeque<Pending> = repositories
+        .iter()
+        .map(|&(owner, name)| Pending {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            pullcursor: None,
+        })
+        .collect();
+
+    while !pending.is_empty() {
+        let batch: Vec<Pending> = pending.drain(..per_batch.min(pending.len())).collect();
+        let last_resp = match client
+            .request_with(&batch_query(&batch, selection, nnodes), backoff)
+            .await
+        {
+            Ok(last_resp) => last_resp,
+            Err(e) => {
+                error!(
+                    "Error returned while querying a batch, retrying one at a time: {}",
+                    e
+                );
+                responses.extend(query_individually(client, batch, nnodes).await);
+                continue;
+            }
+        };
+        if let Some(errors) = last_resp.errors {
+            for e in errors.iter() {
+                error!("GraphQL error: {}", e);
+            }
+        }
+
+        let mut data = match last_resp.data {
+            Some(data) => data,
+            None => {
+                responses.extend(query_individually(client, batch, nnodes).await);
+                continue;
+            }
+        };
+        for (i, repository) in batch.into_iter().enumerate() {
+            match data.repositories.remove(&alias(i)).flatten() {
+                Some(repo_data) => {
+                    let split = repo_view::ResponseData {
+                        repository: Some(repo_data),
+                        rate_limit: data.rate_limit.clone(),
+                    };
+                    if let Some(pullcursor) = RepoView::cursor(&split) {
+                        pending.push_back(Pending {
+                            pullcursor: Some(pullcursor),
+                            ..repository
+                        });
+                    }
+                    responses.push(split);
+                }
+                None => warn!(
+                    "No data returned for {}/{}",
+                    repository.owner, repository.name
+                ),
+            }
+        }
+    }
+
+    Ok(responses)
+}
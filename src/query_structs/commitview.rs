@@ -0,0 +1,81 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::{page_to_end, Cursor},
+    scalars::{DateTime, GitObjectID, GitTimestamp, URI},
+};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::info;
+use std::time::Duration;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/commitquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct CommitView;
+
+/// The default branch's commit history. None for empty repositories or if the branch somehow
+/// points at something other than a commit.
+pub fn commit_history(
+    response: &commit_view::ResponseData,
+) -> Option<&commit_view::CommitViewRepositoryDefaultBranchRefTargetOnCommitHistory> {
+    use commit_view::CommitViewRepositoryDefaultBranchRefTargetOn::Commit;
+
+    let target = response
+        .repository
+        .as_ref()?
+        .default_branch_ref
+        .as_ref()?
+        .target
+        .as_ref()?;
+    match target.on {
+        Commit(ref commit) => Some(&commit.history),
+        _ => None,
+    }
+}
+
+impl Cursor<CommitView> for CommitView {
+    fn cursor(response: &commit_view::ResponseData) -> Option<String> {
+        match commit_history(response) {
+            Some(history) if history.page_info.has_next_page => history
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<CommitView> for CommitView {
+    fn backoff(response: &commit_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+/// Pages through the default branch's history of owner/name.
+pub async fn query_commits_to_end(
+    client: &QueryClient,
+    owner: &str,
+    name: &str,
+    nnodes: i64,
+) -> GGGResult<Vec<commit_view::ResponseData>> {
+    info!("Scraping commits from {}/{}", owner, name);
+
+    page_to_end::<CommitView, _>(client, |commitcursor| {
+        CommitView::build_query(commit_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            commitcursor,
+        })
+    })
+    .await
+}
@@ -0,0 +1,242 @@
+#![warn(clippy::all)]
+use super::{
+    commitview::*,
+    scalars::{GitTimestamp, URI},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Trailer GitHub (and git) use to credit everyone that worked on a commit.
+const CO_AUTHOR_TRAILER: &str = "co-authored-by";
+// Private commit emails are either login@ or id+login@ this domain.
+const NOREPLY_DOMAIN: &str = "@users.noreply.github.com";
+
+/// Whether the commit's author field names the user or a Co-authored-by trailer does.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitRole {
+    Author,
+    CoAuthor,
+}
+
+/// One author (or co-author) of one commit on the default branch.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct CommitViewNode {
+    pub repository: String,
+    pub oid: String,
+    pub url: URI,
+    pub message_headline: String,
+    /// The author's login so that commits line up with pull request participants. Authors
+    /// without a GitHub account are identified by their (lowercased) email instead.
+    pub author: String,
+    pub author_login: Option<String>,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub role: CommitRole,
+    /// Identified the same way as author.
+    pub committer: Option<String>,
+    /// Git keeps the author's and committer's UTC offsets.
+    pub authored_date: Option<GitTimestamp>,
+    pub committed_date: Option<GitTimestamp>,
+    pub additions: i64,
+    pub deletions: i64,
+}
+
+type Commit = commit_view::CommitViewRepositoryDefaultBranchRefTargetOnCommitHistoryEdgesNode;
+
+// Pulls the login out of a GitHub noreply address such as 12345+login@users.noreply.github.com.
+// Domains are case insensitive so hand written trailers may capitalize it.
+fn noreply_login(email: &str) -> Option<&str> {
+    let split = email.len().checked_sub(NOREPLY_DOMAIN.len())?;
+    if !email.get(split..)?.eq_ignore_ascii_case(NOREPLY_DOMAIN) {
+        return None;
+    }
+    let local = &email[..split];
+    Some(local.split_once('+').map_or(local, |(_, login)| login))
+}
+
+// Logins win over emails which win over names. Empty values fall through to the next one.
+fn user_identity(login: Option<&str>, email: Option<&str>, name: Option<&str>) -> Option<String> {
+    let present = |value: &&str| !value.is_empty();
+    login
+        .filter(present)
+        .map(str::to_owned)
+        .or_else(|| email.filter(present).map(str::to_lowercase))
+        .or_else(|| name.filter(present).map(str::to_owned))
+}
+
+// Parses "Co-authored-by: Name <email>" trailers into (name, email) pairs.
+fn co_authors(message: &str) -> Vec<(&str, &str)> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case(CO_AUTHOR_TRAILER) {
+                return None;
+            }
+            let (name, email) = value.trim().rsplit_once('<')?;
+            Some((name.trim(), email.strip_suffix('>')?.trim()))
+        })
+        .collect()
+}
+
+impl CommitViewNode {
+    // Builds the commit's author node followed by a node per co-author. Co-authors repeating the
+    // author or each other are dropped.
+    fn from_commit(repo: &str, commit: &Commit) -> Vec<CommitViewNode> {
+        let author = commit.author.as_ref();
+        let author_login = author
+            .and_then(|author| author.user.as_ref())
+            .map(|user| user.login.clone());
+        let author_email = author.and_then(|author| author.email.clone());
+        let author_name = author.and_then(|author| author.name.clone());
+        let committer = commit.committer.as_ref().and_then(|committer| {
+            user_identity(
+                committer.user.as_ref().map(|user| user.login.as_str()),
+                committer.email.as_deref(),
+                committer.name.as_deref(),
+            )
+        });
+
+        let node = |author, author_login, author_name, author_email, role| CommitViewNode {
+            repository: repo.to_owned(),
+            oid: commit.oid.clone(),
+            url: commit.url.clone(),
+            message_headline: commit.message_headline.clone(),
+            author,
+            author_login,
+            author_name,
+            author_email,
+            role,
+            committer: committer.clone(),
+            authored_date: commit.author.as_ref().and_then(|author| author.date),
+            committed_date: commit
+                .committer
+                .as_ref()
+                .and_then(|committer| committer.date),
+            additions: commit.additions,
+            deletions: commit.deletions,
+        };
+
+        let mut seen = HashSet::new();
+        let mut parsed = Vec::new();
+        if let Some(identity) = user_identity(
+            author_login.as_deref(),
+            author_email.as_deref(),
+            author_name.as_deref(),
+        ) {
+            seen.insert(identity.clone());
+            parsed.push(node(
+                identity,
+                author_login,
+                author_name,
+                author_email,
+                CommitRole::Author,
+            ));
+        }
+
+        for (name, email) in co_authors(&commit.message) {
+            let login = noreply_login(email).map(str::to_owned);
+            if let Some(identity) = user_identity(login.as_deref(), Some(email), Some(name)) {
+                if seen.insert(identity.clone()) {
+                    parsed.push(node(
+                        identity,
+                        login,
+                        Some(name.to_owned()),
+                        Some(email.to_owned()),
+                        CommitRole::CoAuthor,
+                    ));
+                }
+            }
+        }
+        parsed
+    }
+
+    /// Flattens every commit's authors into CommitViewNodes.
+    pub fn parse_nodes(data: &[commit_view::ResponseData]) -> Vec<CommitViewNode> {
+        let mut parsed = Vec::new();
+
+        for unparsed in data.iter() {
+            match (&unparsed.repository, commit_history(unparsed)) {
+                (Some(repo), Some(history)) => {
+                    let commits = history.edges.iter().flatten().flatten();
+                    for commit in commits.filter_map(|edge| edge.node.as_ref()) {
+                        parsed.extend(CommitViewNode::from_commit(&repo.name_with_owner, commit));
+                    }
+                }
+                _ => warn!("Empty data found while parsing. Data: {:#?}", unparsed),
+            }
+        }
+        parsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn co_author_trailers_ignore_case() {
+        let message = "Fix the thing\n\n\
+                       Co-authored-by: Ada Lovelace <ada@example.com>\n\
+                       CO-AUTHORED-BY:Grace Hopper<grace@example.com>\n\
+                       co-authored-by:   Linus <12345+linus@users.noreply.github.com>  ";
+        assert_eq!(
+            co_authors(message),
+            vec![
+                ("Ada Lovelace", "ada@example.com"),
+                ("Grace Hopper", "grace@example.com"),
+                ("Linus", "12345+linus@users.noreply.github.com"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_co_author_trailers_are_skipped() {
+        let message = "Co-authored-by Ada <ada@example.com>\n\
+                       Co-authored-by: Ada ada@example.com\n\
+                       Co-authored-by: Ada <ada@example.com\n\
+                       Signed-off-by: Ada <ada@example.com>";
+        assert!(co_authors(message).is_empty());
+        assert!(co_authors("").is_empty());
+    }
+
+    #[test]
+    fn noreply_logins() {
+        assert_eq!(
+            noreply_login("12345+octocat@users.noreply.github.com"),
+            Some("octocat")
+        );
+        // Accounts older than July 2017 have no ID in their noreply address.
+        assert_eq!(
+            noreply_login("octocat@users.noreply.github.com"),
+            Some("octocat")
+        );
+        assert_eq!(
+            noreply_login("12345+octocat@Users.NoReply.GitHub.com"),
+            Some("octocat")
+        );
+        assert_eq!(noreply_login("octocat@github.com"), None);
+        assert_eq!(noreply_login("github.com"), None);
+        // The domain's length would split the multibyte character.
+        assert_eq!(noreply_login("\u{e9}@users.noreply.github.co"), None);
+    }
+
+    #[test]
+    fn identity_falls_through_empty_values() {
+        assert_eq!(
+            user_identity(Some(""), Some("Ada@Example.com"), Some("Ada")),
+            Some("ada@example.com".to_owned())
+        );
+        assert_eq!(
+            user_identity(Some("ada"), Some("ada@example.com"), None),
+            Some("ada".to_owned())
+        );
+        assert_eq!(
+            user_identity(None, Some(""), Some("Ada")),
+            Some("Ada".to_owned())
+        );
+        assert_eq!(user_identity(None, None, Some("")), None);
+    }
+}
@@ -1,8 +1,45 @@
 #[warn(clippy::all)]
-use graphql_client::GraphQLQuery;
+use super::backoff_timer::BackoffTimer;
+use crate::{error::Result, query_client::QueryClient};
+use graphql_client::{GraphQLQuery, QueryBody};
+use log::error;
 
 pub trait Cursor<R> {
     fn cursor(response: &R::ResponseData) -> Option<String>
     where
         R: GraphQLQuery + Send + Sync;
 }
+
+/// Works like query_to_end for any query with a Cursor. build makes the query for a cursor (None
+/// for the first page).
+pub async fn page_to_end<R, B>(client: &QueryClient, build: B) -> Result<Vec<R::ResponseData>>
+where
+    R: Cursor<R> + BackoffTimer<R> + GraphQLQuery + Send + Sync + Unpin,
+    B: Fn(Option<String>) -> QueryBody<R::Variables>,
+{
+    let mut responses = Vec::new();
+    let mut cursor = None;
+
+    loop {
+        let last_resp = client.request::<_, R>(&build(cursor.take())).await?;
+        if let Some(errors) = last_resp.errors {
+            for e in errors.iter() {
+                error!("GraphQL error: {}", e);
+            }
+        }
+
+        // Stop once there's no cursor to follow or if GitHub only sent back errors.
+        match last_resp.data {
+            Some(data) => {
+                cursor = R::cursor(&data);
+                responses.push(data);
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+
+    Ok(responses)
+}
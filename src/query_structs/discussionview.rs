@@ -0,0 +1,63 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::{page_to_end, Cursor},
+    scalars::{DateTime, URI},
+};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::info;
+use std::time::Duration;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/discussionquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct DiscussionView;
+
+impl Cursor<DiscussionView> for DiscussionView {
+    fn cursor(response: &discussion_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.discussions.page_info.has_next_page => repo_data
+                .discussions
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<DiscussionView> for DiscussionView {
+    fn backoff(response: &discussion_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+/// Pages through the discussions of owner/name. Only the first nnodes comments of each
+/// discussion and replies of each comment are fetched.
+pub async fn query_discussions_to_end(
+    client: &QueryClient,
+    owner: &str,
+    name: &str,
+    nnodes: i64,
+) -> GGGResult<Vec<discussion_view::ResponseData>> {
+    info!("Scraping discussions from {}/{}", owner, name);
+
+    page_to_end::<DiscussionView, _>(client, |discussioncursor| {
+        DiscussionView::build_query(discussion_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            discussioncursor,
+        })
+    })
+    .await
+}
@@ -0,0 +1,153 @@
+#![warn(clippy::all)]
+use super::{
+    discussionview::*,
+    repoview_nodes::Role,
+    scalars::{DateTime, URI},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// How a user took part in a discussion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscussionInteraction {
+    /// Started the discussion.
+    Opened,
+    /// A top level comment.
+    Commented,
+    /// A reply in one of the comments' threads.
+    Replied,
+    /// Wrote the comment chosen as the answer.
+    Answered,
+}
+
+/// One interaction with one discussion. The participant, role, and author mirror RepoViewNode so
+/// that discussions can feed the same graph as pull requests and issues.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct DiscussionViewNode {
+    pub repository: String,
+    pub participant: String,
+    pub role: Role,
+    pub interaction: DiscussionInteraction,
+    /// None for deleted ("ghost") accounts.
+    pub discussion_author: Option<String>,
+    /// The author of the comment a reply was made to.
+    pub replied_to: Option<String>,
+    /// GitHub's node ID of the top level comment that a comment or reply belongs to.
+    pub thread_id: Option<String>,
+    /// When the discussion was started.
+    pub date_created: DateTime,
+    /// When the interaction happened. Answers are dated by when they were chosen.
+    pub date: DateTime,
+    pub discussion_title: String,
+    pub discussion_number: i64,
+    pub discussion_url: URI,
+    pub discussion_id: String,
+    pub category: String,
+}
+
+type Discussion = discussion_view::DiscussionViewRepositoryDiscussionsEdgesNode;
+
+impl DiscussionViewNode {
+    // Builds the nodes for the discussion's author, commenters, repliers, and answer author in
+    // that order. Interactions from deleted accounts are skipped.
+    fn from_discussion(repo: &str, discussion: &Discussion) -> Vec<DiscussionViewNode> {
+        let discussion_author = discussion
+            .author
+            .as_ref()
+            .map(|author| author.login.clone());
+        let node =
+            |participant: &str, interaction, date, replied_to, thread_id| DiscussionViewNode {
+                repository: repo.to_owned(),
+                participant: participant.to_owned(),
+                role: if discussion_author.as_deref() == Some(participant) {
+                    Role::Author
+                } else {
+                    Role::Participant
+                },
+                interaction,
+                discussion_author: discussion_author.clone(),
+                replied_to,
+                thread_id,
+                date_created: discussion.created_at,
+                date,
+                discussion_title: discussion.title.clone(),
+                discussion_number: discussion.number,
+                discussion_url: discussion.url.clone(),
+                discussion_id: discussion.id.clone(),
+                category: discussion.category.name.clone(),
+            };
+
+        let mut parsed = Vec::new();
+        if let Some(ref author) = discussion_author {
+            parsed.push(node(
+                author,
+                DiscussionInteraction::Opened,
+                discussion.created_at,
+                None,
+                None,
+            ));
+        }
+
+        let comments = discussion.comments.nodes.iter().flatten().flatten();
+        for comment in comments {
+            let commenter = comment.author.as_ref().map(|author| author.login.clone());
+            if let Some(ref commenter) = commenter {
+                parsed.push(node(
+                    commenter,
+                    DiscussionInteraction::Commented,
+                    comment.created_at,
+                    None,
+                    Some(comment.id.clone()),
+                ));
+            }
+
+            let replies = comment.replies.nodes.iter().flatten().flatten();
+            for reply in replies {
+                if let Some(ref author) = reply.author {
+                    parsed.push(node(
+                        &author.login,
+                        DiscussionInteraction::Replied,
+                        reply.created_at,
+                        commenter.clone(),
+                        Some(comment.id.clone()),
+                    ));
+                }
+            }
+        }
+
+        if let Some(ref answer) = discussion.answer {
+            if let Some(ref author) = answer.author {
+                parsed.push(node(
+                    &author.login,
+                    DiscussionInteraction::Answered,
+                    discussion.answer_chosen_at.unwrap_or(answer.created_at),
+                    None,
+                    None,
+                ));
+            }
+        }
+        parsed
+    }
+
+    /// Flattens every discussion's interactions into DiscussionViewNodes.
+    pub fn parse_nodes(data: &[discussion_view::ResponseData]) -> Vec<DiscussionViewNode> {
+        let mut parsed = Vec::new();
+
+        for unparsed in data.iter() {
+            match unparsed.repository {
+                Some(ref repo) => {
+                    let discussions = repo.discussions.edges.iter().flatten().flatten();
+                    for discussion in discussions.filter_map(|edge| edge.node.as_ref()) {
+                        parsed.extend(DiscussionViewNode::from_discussion(
+                            &repo.name_with_owner,
+                            discussion,
+                        ));
+                    }
+                }
+                None => warn!("Empty data found while parsing. Data: {:#?}", unparsed),
+            }
+        }
+        parsed
+    }
+}
@@ -0,0 +1,63 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::{page_to_end, Cursor},
+    scalars::{DateTime, URI},
+};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::info;
+use std::time::Duration;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/issuequery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct IssueView;
+
+impl Cursor<IssueView> for IssueView {
+    fn cursor(response: &issue_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.issues.page_info.has_next_page => repo_data
+                .issues
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<IssueView> for IssueView {
+    fn backoff(response: &issue_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+/// Pages through every issue of owner/name. Only the first nnodes labels and participants of
+/// each issue are fetched.
+pub async fn query_issues_to_end(
+    client: &QueryClient,
+    owner: &str,
+    name: &str,
+    nnodes: i64,
+) -> GGGResult<Vec<issue_view::ResponseData>> {
+    info!("Scraping issues from {}/{}", owner, name);
+
+    page_to_end::<IssueView, _>(client, |issuecursor| {
+        IssueView::build_query(issue_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            issuecursor,
+        })
+    })
+    .await
+}
@@ -0,0 +1,150 @@
+#![warn(clippy::all)]
+use super::{
+    issueview::*,
+    profileview_nodes::UserProfile,
+    repoview_nodes::Role,
+    scalars::{DateTime, URI},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// One participant of one issue. Mirrors RepoViewNode so that issues can feed the same graph.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct IssueViewNode {
+    pub repository: String,
+    pub participant: String,
+    pub role: Role,
+    /// None for deleted ("ghost") accounts.
+    pub issue_author: Option<String>,
+    pub author_association: String,
+    pub date_created: DateTime,
+    pub closed_at: Option<DateTime>,
+    pub issue_title: String,
+    pub issue_number: i64,
+    pub issue_url: URI,
+    pub issue_id: String,
+    /// OPEN or CLOSED.
+    pub state: String,
+    pub labels: Vec<String>,
+    pub location: Option<String>,
+    pub company: Option<String>,
+    pub organizations: Vec<String>,
+    pub unknown_organizations: usize,
+}
+
+type IssueNode = issue_view::IssueViewRepositoryIssuesEdgesNode;
+type ParticipantNode = issue_view::IssueViewRepositoryIssuesEdgesNodeParticipantsEdgesNode;
+
+impl IssueViewNode {
+    fn association_to_string(association: &issue_view::CommentAuthorAssociation) -> String {
+        use issue_view::CommentAuthorAssociation::*;
+        match association {
+            COLLABORATOR => "COLLABORATOR",
+            CONTRIBUTOR => "CONTRIBUTOR",
+            FIRST_TIMER => "FIRST_TIMER",
+            FIRST_TIME_CONTRIBUTOR => "FIRST_TIME_CONTRIBUTOR",
+            MANNEQUIN => "MANNEQUIN",
+            MEMBER => "MEMBER",
+            NONE => "NONE",
+            OWNER => "OWNER",
+            Other(other) => other.as_str(),
+        }
+        .to_owned()
+    }
+
+    fn state_to_string(state: &issue_view::IssueState) -> String {
+        use issue_view::IssueState::*;
+        match state {
+            CLOSED => "CLOSED",
+            OPEN => "OPEN",
+            Other(other) => other.as_str(),
+        }
+        .to_owned()
+    }
+
+    // Builds the node for a single participant. As with RepoViewNode, the profile is filled in
+    // later.
+    fn from_participant(repo: &str, issue: &IssueNode, user: &ParticipantNode) -> IssueViewNode {
+        let issue_author = issue.author.as_ref().map(|author| author.login.clone());
+
+        IssueViewNode {
+            repository: repo.to_owned(),
+            participant: user.login.clone(),
+            role: if issue_author.as_deref() == Some(user.login.as_str()) {
+                Role::Author
+            } else {
+                Role::Participant
+            },
+            issue_author,
+            author_association: IssueViewNode::association_to_string(&issue.author_association),
+            date_created: issue.created_at,
+            closed_at: issue.closed_at,
+            issue_title: issue.title.clone(),
+            issue_number: issue.number,
+            issue_url: issue.url.clone(),
+            issue_id: issue.id.clone(),
+            state: IssueViewNode::state_to_string(&issue.state),
+            labels: issue
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.nodes.as_ref())
+                .map_or_else(Vec::new, |labels| {
+                    labels
+                        .iter()
+                        .flatten()
+                        .map(|label| label.name.clone())
+                        .collect()
+                }),
+            location: None,
+            company: None,
+            organizations: Vec::new(),
+            unknown_organizations: 0,
+        }
+    }
+
+    /// Copies the participant's location, company, and organizations from their profile.
+    pub fn fill_profile(&mut self, profiles: &HashMap<&str, &UserProfile>) {
+        if let Some(profile) = profiles.get(self.participant.as_str()) {
+            self.location = profile.location.clone();
+            self.company = profile.company.clone();
+            self.organizations = profile.organizations.clone();
+            self.unknown_organizations = profile.unknown_organizations;
+        }
+    }
+
+    /// Same as RepoViewNode::participant_ids but for issues.
+    pub fn participant_ids(data: &[issue_view::ResponseData]) -> BTreeMap<String, String> {
+        data.iter()
+            .filter_map(|unparsed| unparsed.repository.as_ref())
+            .flat_map(|repo| repo.issues.edges.iter().flatten().flatten())
+            .filter_map(|edge| edge.node.as_ref())
+            .flat_map(|issue| issue.participants.edges.iter().flatten().flatten())
+            .filter_map(|edge| edge.node.as_ref())
+            .map(|user| (user.id.clone(), user.login.clone()))
+            .collect()
+    }
+
+    /// Flattens every issue's participants into IssueViewNodes.
+    pub fn parse_nodes(data: &[issue_view::ResponseData]) -> Vec<IssueViewNode> {
+        let mut parsed = Vec::new();
+
+        for unparsed in data.iter() {
+            match unparsed.repository {
+                Some(ref repo) => {
+                    let issues = repo.issues.edges.iter().flatten().flatten();
+                    for issue in issues.filter_map(|edge| edge.node.as_ref()) {
+                        let participants = issue.participants.edges.iter().flatten().flatten();
+                        parsed.extend(participants.filter_map(|edge| edge.node.as_ref()).map(
+                            |user| {
+                                IssueViewNode::from_participant(&repo.name_with_owner, issue, user)
+                            },
+                        ));
+                    }
+                }
+                None => warn!("Empty data found while parsing. Data: {:#?}", unparsed),
+            }
+        }
+        parsed
+    }
+}
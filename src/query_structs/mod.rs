@@ -1,5 +1,26 @@
+pub mod adoptionview;
+pub mod adoptionview_nodes;
 pub mod backoff_timer;
+pub mod batchview;
+pub mod commitview;
+pub mod commitview_nodes;
 pub mod cursor;
+pub mod discussionview;
+pub mod discussionview_nodes;
+pub mod issueview;
+pub mod issueview_nodes;
+pub mod profileview;
+pub mod profileview_nodes;
+pub mod query_cost;
+pub mod ratelimitview;
+pub mod read_nodes;
 pub mod repoview;
 pub mod repoview_nodes;
+pub mod review_nodes;
+pub mod reviewview;
+pub mod scalars;
+pub mod tables;
+pub mod write_csv;
 pub mod write_nodes;
+pub mod write_parquet;
+pub mod write_sqlite;
@@ -0,0 +1,77 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    scalars::DateTime,
+};
+use crate::query_client::QueryClient;
+use graphql_client::GraphQLQuery;
+use log::{error, info};
+use std::time::Duration;
+
+// GitHub refuses to look up more than 100 IDs at a time.
+pub const MAX_IDS: usize = 100;
+
+// websiteUrl is whatever users typed into their profile, which doesn't always parse as a URL.
+// Shadowing the URI scalar keeps it as text rather than failing the user's whole batch.
+#[allow(clippy::upper_case_acronyms)]
+type URI = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/profilequery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct ProfileView;
+
+impl BackoffTimer<ProfileView> for ProfileView {
+    fn backoff(response: &profile_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+/// Looks up the profiles of the users with the given (node ID, login) pairs, up to 100 per
+/// request. Users should already be deduplicated since every ID costs a node against the rate
+/// limit. Batches that fail are logged by login and skipped so the rest of the profiles are
+/// still returned.
+pub async fn query_profiles(
+    client: &QueryClient,
+    users: &[(String, String)],
+    nnodes: i64,
+) -> Vec<profile_view::ResponseData> {
+    let mut responses = Vec::new();
+    info!("Scraping {} user profiles", users.len());
+
+    for batch in users.chunks(MAX_IDS) {
+        let query = ProfileView::build_query(profile_view::Variables {
+            ids: batch.iter().map(|(id, _)| id.clone()).collect(),
+            nnodes,
+        });
+        let last_resp = match client.request::<_, ProfileView>(&query).await {
+            Ok(last_resp) => last_resp,
+            Err(e) => {
+                let logins: Vec<&str> = batch.iter().map(|(_, login)| login.as_str()).collect();
+                error!(
+                    "Error returned while querying the profiles of {}: {}",
+                    logins.join(", "),
+                    e
+                );
+                continue;
+            }
+        };
+        // Users deleted since the pull requests were scraped come back as errors alongside the
+        // remaining profiles.
+        if let Some(errors) = last_resp.errors {
+            for e in errors.iter() {
+                error!("GraphQL error: {}", e);
+            }
+        }
+        responses.extend(last_resp.data);
+    }
+
+    responses
+}
@@ -0,0 +1,79 @@
+#![warn(clippy::all)]
+use super::{profileview::*, scalars::DateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A user's profile, fetched once per user rather than once per pull request they took part in.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct UserProfile {
+    pub login: String,
+    /// GitHub's global node ID for the user.
+    pub id: String,
+    pub name: Option<String>,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    pub company: Option<String>,
+    pub organizations: Vec<String>,
+    /// Organizations GitHub listed without a login (e.g. hidden from the token's scopes).
+    pub unknown_organizations: usize,
+    pub created_at: DateTime,
+    pub followers: i64,
+    pub twitter: Option<String>,
+    /// Free text from the user's profile so it isn't necessarily a valid URL.
+    pub website: Option<String>,
+}
+
+type User = profile_view::ProfileViewNodesOnUser;
+
+impl UserProfile {
+    fn from_user(user: &User) -> UserProfile {
+        let (organizations, unknown_organizations) =
+            user.organizations
+                .nodes
+                .as_ref()
+                .map_or((Vec::new(), 0), |nodes| {
+                    let logins: Vec<String> = nodes
+                        .iter()
+                        .filter_map(|org| org.as_ref().map(|org| org.login.clone()))
+                        .collect();
+                    let unknown = nodes.len() - logins.len();
+                    (logins, unknown)
+                });
+
+        UserProfile {
+            login: user.login.clone(),
+            id: user.id.clone(),
+            name: user.name.clone(),
+            bio: user.bio.clone(),
+            location: user.location.clone(),
+            company: user.company.clone(),
+            organizations,
+            unknown_organizations,
+            created_at: user.created_at,
+            followers: user.followers.total_count,
+            twitter: user.twitter_username.clone(),
+            website: user.website_url.clone(),
+        }
+    }
+
+    /// Pulls the users out of every batch. IDs that no longer resolve to a user are skipped.
+    pub fn parse_nodes(data: &[profile_view::ResponseData]) -> Vec<UserProfile> {
+        use profile_view::ProfileViewNodesOn::User;
+
+        let mut parsed = Vec::new();
+        for node in data.iter().flat_map(|batch| batch.nodes.iter().flatten()) {
+            if let User(ref user) = node.on {
+                parsed.push(UserProfile::from_user(user));
+            }
+        }
+        parsed
+    }
+
+    /// Indexes the profiles by login.
+    pub fn by_login(profiles: &[UserProfile]) -> HashMap<&str, &UserProfile> {
+        profiles
+            .iter()
+            .map(|profile| (profile.login.as_str(), profile))
+            .collect()
+    }
+}
@@ -0,0 +1,165 @@
+#![warn(clippy::all)]
+/// GitHub refuses queries that may return more than this many nodes.
+pub const MAX_NODES: i64 = 500_000;
+// GitHub charges a point per 100 requests it needs to fill a query's connections.
+const REQUESTS_PER_POINT: i64 = 100;
+
+/// GitHub's worst case cost of a query as described in "Resource limitations" of the GraphQL API
+/// docs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct QueryCost {
+    /// Nodes returned if every connection is full.
+    pub nodes: i64,
+    /// Requests GitHub makes to fill every connection.
+    pub requests: i64,
+}
+
+impl QueryCost {
+    /// Estimates the cost of a query document by multiplying each connection's first argument by
+    /// those of the connections it's nested in. Any variable passed as first is assumed to be
+    /// nnodes.
+    pub fn estimate(document: &str, nnodes: i64) -> Self {
+        let mut cost = QueryCost::default();
+        // Page size of each open selection set; 1 for anything that isn't a connection.
+        let mut sizes: Vec<i64> = Vec::new();
+        let mut first = None;
+        for (i, c) in document.char_indices() {
+            match c {
+                '(' => {
+                    let arguments = &document[i + 1..];
+                    let arguments = &arguments[..arguments.find(')').unwrap_or(arguments.len())];
+                    first = first_argument(arguments, nnodes);
+                }
+                '{' => {
+                    let parent: i64 = sizes.iter().product();
+                    match first.take() {
+                        Some(size) => {
+                            cost.requests += parent;
+                            cost.nodes += parent * size;
+                            sizes.push(size);
+                        }
+                        None => sizes.push(1),
+                    }
+                }
+                '}' => {
+                    first = None;
+                    sizes.pop();
+                }
+                _ => (),
+            }
+        }
+        cost
+    }
+
+    /// Rate limit points GitHub charges for the query (at least one).
+    pub fn points(self) -> i64 {
+        ((self.requests + REQUESTS_PER_POINT / 2) / REQUESTS_PER_POINT).max(1)
+    }
+}
+
+// Pulls the page size out of an argument list such as "first: $nnodes, after: $cursor".
+fn first_argument(arguments: &str, nnodes: i64) -> Option<i64> {
+    arguments.split(',').find_map(|argument| {
+        let (name, value) = argument.split_once(':')?;
+        if name.trim() != "first" {
+            return None;
+        }
+        let value = value.trim();
+        if value.starts_with('$') {
+            Some(nnodes)
+        } else {
+            value.parse().ok()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The two examples from "Resource limitations" in GitHub's GraphQL API docs.
+    const SMALL: &str = "query {
+        viewer {
+            repositories(first: 50) {
+                edges {
+                    repository:node {
+                        name
+                        issues(first: 10) {
+                            totalCount
+                            edges { node { title bodyHTML } }
+                        }
+                    }
+                }
+            }
+        }
+    }";
+    const LARGE: &str = "query {
+        viewer {
+            repositories(first: 100) {
+                edges {
+                    repository:node {
+                        name
+                        pullRequests(first: 100) {
+                            edges {
+                                pullRequest:node {
+                                    title
+                                    comments(first: 10) { edges { comment:node { bodyHTML } } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }";
+
+    #[test]
+    fn documented_examples() {
+        let small = QueryCost::estimate(SMALL, 100);
+        assert_eq!(
+            small,
+            QueryCost {
+                nodes: 50 + 50 * 10,
+                requests: 1 + 50,
+            }
+        );
+        assert_eq!(small.points(), 1);
+
+        let large = QueryCost::estimate(LARGE, 100);
+        assert_eq!(
+            large,
+            QueryCost {
+                nodes: 100 + 100 * 100 + 100 * 100 * 10,
+                requests: 1 + 100 + 100 * 100,
+            }
+        );
+        assert_eq!(large.points(), 101);
+    }
+
+    #[test]
+    fn variables_are_nnodes() {
+        let document = "query($owner: String!, $nnodes: Int!, $cursor: String) {
+            repository(owner: $owner, name: \"crate\") {
+                pullRequests(first: $nnodes, after: $cursor) {
+                    nodes { participants(first: 10) { nodes { login } } }
+                }
+                labels(first: 5) { nodes { name } }
+            }
+        }";
+        assert_eq!(
+            QueryCost::estimate(document, 20),
+            QueryCost {
+                nodes: 20 + 20 * 10 + 5,
+                requests: 1 + 20 + 1,
+            }
+        );
+    }
+
+    #[test]
+    fn arguments_other_than_first_are_ignored() {
+        assert_eq!(first_argument("after: $cursor, first: 25", 100), Some(25));
+        assert_eq!(first_argument("owner: $owner, name: $name", 100), None);
+        assert_eq!(first_argument("firstName: 3", 100), None);
+        assert_eq!(QueryCost::estimate("{ viewer { login } }", 100).points(), 1);
+    }
+}
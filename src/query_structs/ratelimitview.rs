@@ -0,0 +1,37 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{backoff_timer::BackoffTimer, scalars::DateTime};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::error;
+use std::time::Duration;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/ratelimitquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct RateLimitView;
+
+// Dry runs don't cost anything so there's no reason to pause afterwards.
+impl BackoffTimer<RateLimitView> for RateLimitView {
+    fn backoff(_response: &rate_limit_view::ResponseData) -> Option<Duration> {
+        Some(Duration::from_secs(0))
+    }
+}
+
+/// Asks GitHub for the token's current rate limit without spending any of it.
+pub async fn query_rate_limit(
+    client: &QueryClient,
+) -> GGGResult<Option<rate_limit_view::RateLimitViewRateLimit>> {
+    let last_resp = client
+        .request::<_, RateLimitView>(&RateLimitView::build_query(rate_limit_view::Variables))
+        .await?;
+    if let Some(errors) = last_resp.errors {
+        for e in errors.iter() {
+            error!("GraphQL error: {}", e);
+        }
+    }
+    Ok(last_resp.data.and_then(|data| data.rate_limit))
+}
@@ -0,0 +1,26 @@
+#![warn(clippy::all)]
+use crate::error::{Error, ErrorKind, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{from_reader, Deserializer};
+use std::{fs::File, io::BufReader, path::Path};
+
+/// Reads nodes back from a file produced by write_nodes or write_nodes_jsonl. Files ending in
+/// .jsonl or .ndjson are read as JSON Lines; anything else is read as a JSON array.
+pub fn read_nodes<I, P>(path: P) -> Result<Vec<I>>
+where
+    I: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let reader = BufReader::new(
+        File::open(path)
+            .map_err(|e| Error::new(format!("Opening {}", path.display()), ErrorKind::Io(e)))?,
+    );
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("jsonl") | Some("ndjson") => Ok(Deserializer::from_reader(reader)
+            .into_iter::<I>()
+            .collect::<std::result::Result<Vec<I>, _>>()?),
+        _ => Ok(from_reader(reader)?),
+    }
+}
@@ -1,26 +1,21 @@
 #[warn(clippy::all)]
-use super::{backoff_timer::BackoffTimer, cursor::Cursor};
+// The GitHub GraphQL schema defines types that don't necessarily map to Rust types.
+// The custom scalars must be in scope as rustc throws an error originating from the macro
+// otherwise.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::Cursor,
+    scalars::{DateTime, URI},
+};
 // Importing error::Result breaks #[derive(GraphQLQuery)] for some reason.
 use crate::{error::Result as GGGResult, query_client::QueryClient};
-use chrono::{offset::Utc, Duration as OldDuration};
 use graphql_client::{GraphQLQuery, QueryBody, Response};
-use log::{error, info, warn};
+use log::{error, info};
 use std::time::Duration;
 
-// The GitHub GraphQL schema defines types that don't necessarily map to Rust types.
-// We'll need to define types such as URI ourselves as rustc throws an error originating from the macro otherwise.
-// type URI = String;
-type DateTime = String;
-
 // Typing QueryBody<repo_view::Variables> gets old :(
 type RepoQuery = QueryBody<repo_view::Variables>;
 
-// Defaults
-// Minutes is signed and seconds is unsigned due to the type constraints for the two different
-// durations. I don't see a reason to make them the same type and convert.
-const SLEEP_MINUTES: i64 = 15;
-const SLEEP_SEC: u64 = 900;
-
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "queries/ghschema.graphql",
@@ -49,40 +44,10 @@ impl Cursor<RepoView> for RepoView {
 
 impl BackoffTimer<RepoView> for RepoView {
     fn backoff(response: &repo_view::ResponseData) -> Option<Duration> {
-        match &response.rate_limit {
-            Some(ratelimit) if ratelimit.remaining == 0 => {
-                // The following is a bit messy but hopefully simple to follow.
-                // I don't want to return any errors because handling them in a higher context
-                // would be messy when waiting for a default time would be easier. However, parsing
-                // the rate limit DateTime String or subtracting from Utc::now() shouldn't really
-                // fail so printing a message seems like a good warning.
-                let reset_at = chrono::DateTime::parse_from_rfc3339(&ratelimit.reset_at)
-                    .unwrap_or_else(|e| {
-                        warn!(
-                            "Error parsing an ostensibly existing RateLimit DateTime: {}",
-                            e
-                        );
-                        // Notify the user if we can't parse the rate_limit DateTime followed by
-                        // returning a default sleep time.
-                        (Utc::now() + OldDuration::minutes(SLEEP_MINUTES)).into()
-                    });
-                info!("Rate limit reached. Sleeping until: {}", reset_at);
-                // Same process as above. Check if the conversion is okay or return the default.
-                Some(
-                    (chrono::DateTime::<Utc>::from(reset_at) - Utc::now())
-                        .to_std()
-                        .unwrap_or_else(|e| {
-                            warn!("{}", e);
-                            Duration::from_secs(SLEEP_SEC)
-                        }),
-                )
-            }
-            Some(ratelimit) => {
-                info!("Queries remaining before pausing: {}", ratelimit.remaining);
-                None
-            }
-            _ => None,
-        }
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
     }
 }
 
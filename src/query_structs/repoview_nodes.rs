@@ -1,54 +1,128 @@
-use super::repoview::*;
+use super::{
+    profileview_nodes::UserProfile,
+    repoview::*,
+    scalars::{DateTime, URI},
+};
 #[warn(clippy::all)]
 //use crate::error::{Error, Result};
 use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Whether a participant opened the pull request or only took part in it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Author,
+    // Nodes scraped before roles were recorded don't know who opened the pull request.
+    #[default]
+    Participant,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Participant => "participant",
+        }
+    }
+}
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct RepoViewNode {
     pub repository: String,
-    pub author: String,
-    pub date_created: String,
+    /// Login of the user this node describes. Older output called this "author".
+    #[serde(alias = "author")]
+    pub participant: String,
+    #[serde(default)]
+    pub role: Role,
+    /// Login of the user that opened the pull request. None for deleted ("ghost") accounts.
+    #[serde(default)]
+    pub pr_author: Option<String>,
+    /// The pull request author's relationship to the repository (MEMBER, CONTRIBUTOR, etc.).
+    #[serde(default)]
+    pub author_association: String,
+    pub date_created: DateTime,
     pub pull_req_title: String,
-    pub location: String,
-    pub company: String,
+    // Older output lacks the pull request's identity and merge information. The number is 0 and
+    // the strings are empty for those nodes.
+    #[serde(default)]
+    pub pr_number: i64,
+    pub pr_url: Option<URI>,
+    /// GitHub's global node ID for the pull request.
+    #[serde(default)]
+    pub pr_id: String,
+    /// OPEN, CLOSED, or MERGED.
+    #[serde(default)]
+    pub state: String,
+    pub merged_at: Option<DateTime>,
+    pub closed_at: Option<DateTime>,
+    pub merged_by: Option<String>,
+    #[serde(default)]
+    pub additions: i64,
+    #[serde(default)]
+    pub deletions: i64,
+    #[serde(default)]
+    pub changed_files: i64,
+    /// Users don't have to list a location, company, or organizations. These are filled in from
+    /// the users' profiles after the pull requests are scraped.
+    pub location: Option<String>,
+    pub company: Option<String>,
     pub organizations: Vec<String>,
+    /// Organizations GitHub listed without a login (e.g. hidden from the token's scopes).
+    #[serde(default)]
+    pub unknown_organizations: usize,
 }
 
 impl RepoViewNode {
-    // Pulls out each organization from the array of organizations listed by the user.
-    fn organizations_to_vec(
-        orgs: &repo_view::RepoViewRepositoryPullRequestsEdgesNodeParticipantsEdgesNodeOrganizations,
-    ) -> Option<Vec<String>> {
-        orgs.nodes.as_ref().and_then(|nodes_iter| {
-            Some(
-                nodes_iter
-                    .iter()
-                    .map(|node_org| {
-                        node_org
-                            .as_ref()
-                            .and_then(|org| Some(org.login.clone()))
-                            .unwrap_or_else(|| String::from("NA"))
-                    })
-                    .collect(),
-            )
-        })
+    // GitHub's CommentAuthorAssociation as it's spelled in the schema.
+    fn association_to_string(association: &repo_view::CommentAuthorAssociation) -> String {
+        use repo_view::CommentAuthorAssociation::*;
+        match association {
+            COLLABORATOR => "COLLABORATOR",
+            CONTRIBUTOR => "CONTRIBUTOR",
+            FIRST_TIMER => "FIRST_TIMER",
+            FIRST_TIME_CONTRIBUTOR => "FIRST_TIME_CONTRIBUTOR",
+            MANNEQUIN => "MANNEQUIN",
+            MEMBER => "MEMBER",
+            NONE => "NONE",
+            OWNER => "OWNER",
+            Other(other) => other.as_str(),
+        }
+        .to_owned()
+    }
+
+    fn state_to_string(state: &repo_view::PullRequestState) -> String {
+        use repo_view::PullRequestState::*;
+        match state {
+            CLOSED => "CLOSED",
+            MERGED => "MERGED",
+            OPEN => "OPEN",
+            Other(other) => other.as_str(),
+        }
+        .to_owned()
     }
 
     // My attempt to break up the rightward drift in destructuring and parsing the JSON output from
     // my GraphQL query. The following function builds RepoViewNodes from
     // RepoViewRepositoryPullRequestsEdgesNodeParticipantsEdges. Sorry, I just wanted to type that
     // again.
-    // Participants refers to posters on the specific pull request. So, we take in repository and
-    // DateTime String slices as those don't change per poster.
+    // Participants refers to posters on the specific pull request (including whoever opened it).
+    // So, we take in the repository and the pull request itself as those don't change per poster.
     fn participants_to_nodes(
         participants: &Vec<
             Option<repo_view::RepoViewRepositoryPullRequestsEdgesNodeParticipantsEdges>,
         >,
         repo: &str,
-        created_at: &str,
-        title: &str,
+        pull_request: &repo_view::RepoViewRepositoryPullRequestsEdgesNode,
     ) -> Option<Vec<RepoViewNode>> {
+        let pr_author = pull_request
+            .author
+            .as_ref()
+            .map(|author| author.login.as_str());
+        let association = RepoViewNode::association_to_string(&pull_request.author_association);
+        let state = RepoViewNode::state_to_string(&pull_request.state);
+
         participants
             .iter()
             .map(|part_edges_opt| {
@@ -59,21 +133,33 @@ impl RepoViewNode {
                     part_edges.node.as_ref().and_then(|user| {
                         Some(RepoViewNode {
                             repository: repo.to_owned(),
-                            author: user.login.to_owned(),
-                            date_created: created_at.to_owned(),
-                            pull_req_title: title.to_owned(),
-                            // Users don't have to specify a location/company/organizations so they
-                            // must be handled reasonably.
-                            location: user
-                                .location
-                                .as_ref()
-                                .map_or_else(|| String::from("NA"), |location| location.clone()),
-                            company: user
-                                .company
+                            participant: user.login.to_owned(),
+                            role: if pr_author == Some(user.login.as_str()) {
+                                Role::Author
+                            } else {
+                                Role::Participant
+                            },
+                            pr_author: pr_author.map(str::to_owned),
+                            author_association: association.clone(),
+                            date_created: pull_request.created_at,
+                            pull_req_title: pull_request.title.clone(),
+                            pr_number: pull_request.number,
+                            pr_url: Some(pull_request.url.clone()),
+                            pr_id: pull_request.id.clone(),
+                            state: state.clone(),
+                            merged_at: pull_request.merged_at,
+                            closed_at: pull_request.closed_at,
+                            merged_by: pull_request
+                                .merged_by
                                 .as_ref()
-                                .map_or_else(|| String::from("NA"), |company| company.clone()),
-                            organizations: RepoViewNode::organizations_to_vec(&user.organizations)
-                                .unwrap_or_else(|| Vec::new()),
+                                .map(|merger| merger.login.clone()),
+                            additions: pull_request.additions,
+                            deletions: pull_request.deletions,
+                            changed_files: pull_request.changed_files,
+                            location: None,
+                            company: None,
+                            organizations: Vec::new(),
+                            unknown_organizations: 0,
                         }) //End of RepoViewNode construction
                     }) // End of ParticipantsEdgesNode
                 }) // End of individual ParticipantsEdges
@@ -105,8 +191,7 @@ impl RepoViewNode {
                                 Some(RepoViewNode::participants_to_nodes(
                                     &part_edges_iter,
                                     repo,
-                                    &pr_edges_node.created_at,
-                                    &pr_edges_node.title,
+                                    pr_edges_node,
                                 ))
                             }) // End of ParticipantsEdges iter
                     }) // End of RepoViewRepositoryPullRequestsEdgesNode
@@ -121,6 +206,28 @@ impl RepoViewNode {
             .collect()
     }
 
+    /// Copies the participant's location, company, and organizations from their profile.
+    pub fn fill_profile(&mut self, profiles: &HashMap<&str, &UserProfile>) {
+        if let Some(profile) = profiles.get(self.participant.as_str()) {
+            self.location = profile.location.clone();
+            self.company = profile.company.clone();
+            self.organizations = profile.organizations.clone();
+            self.unknown_organizations = profile.unknown_organizations;
+        }
+    }
+
+    /// The node ID (and login) of every participant so that each profile is only fetched once.
+    pub fn participant_ids(data: &[repo_view::ResponseData]) -> BTreeMap<String, String> {
+        data.iter()
+            .filter_map(|unparsed| unparsed.repository.as_ref())
+            .flat_map(|repo| repo.pull_requests.edges.iter().flatten().flatten())
+            .filter_map(|pr_edge| pr_edge.node.as_ref())
+            .flat_map(|pull_request| pull_request.participants.edges.iter().flatten().flatten())
+            .filter_map(|part_edge| part_edge.node.as_ref())
+            .map(|user| (user.id.clone(), user.login.clone()))
+            .collect()
+    }
+
     pub fn parse_nodes(data: &Vec<repo_view::ResponseData>) -> Vec<RepoViewNode> {
         let mut parsed: Vec<RepoViewNode> = Vec::new();
 
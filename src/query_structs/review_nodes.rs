@@ -0,0 +1,126 @@
+#![warn(clippy::all)]
+use super::{reviewview::*, scalars::DateTime};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// How a reviewer interacted with a pull request.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interaction {
+    /// A submitted review (approval, change request, or comment).
+    Review,
+    /// A comment in one of the pull request's review threads.
+    ReviewComment,
+}
+
+/// A single review or review comment on a pull request. Unlike RepoViewNode's participants these
+/// say who reviewed whom so they may be drawn as pull request author -> reviewer edges.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct ReviewNode {
+    pub repository: String,
+    pub pr_number: i64,
+    pub pr_id: String,
+    /// None for deleted ("ghost") accounts.
+    pub pr_author: Option<String>,
+    pub reviewer: String,
+    pub interaction: Interaction,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED, DISMISSED, or PENDING for reviews. Review comments
+    /// don't have a state.
+    pub state: Option<String>,
+    /// Pending reviews haven't been submitted yet.
+    pub submitted_at: Option<DateTime>,
+    /// GitHub's node ID of the review thread a comment belongs to.
+    pub thread_id: Option<String>,
+}
+
+type PullRequest = review_view::ReviewViewRepositoryPullRequestsEdgesNode;
+
+impl ReviewNode {
+    fn state_to_string(state: &review_view::PullRequestReviewState) -> String {
+        use review_view::PullRequestReviewState::*;
+        match state {
+            APPROVED => "APPROVED",
+            CHANGES_REQUESTED => "CHANGES_REQUESTED",
+            COMMENTED => "COMMENTED",
+            DISMISSED => "DISMISSED",
+            PENDING => "PENDING",
+            Other(other) => other.as_str(),
+        }
+        .to_owned()
+    }
+
+    // Reviews and review comments from deleted accounts are skipped since there's no one to
+    // connect them to.
+    fn from_pull_request(repo: &str, pull_request: &PullRequest) -> Vec<ReviewNode> {
+        let pr_author = pull_request
+            .author
+            .as_ref()
+            .map(|author| author.login.clone());
+        let node = |reviewer: &str, interaction, state, submitted_at, thread_id| ReviewNode {
+            repository: repo.to_owned(),
+            pr_number: pull_request.number,
+            pr_id: pull_request.id.clone(),
+            pr_author: pr_author.clone(),
+            reviewer: reviewer.to_owned(),
+            interaction,
+            state,
+            submitted_at,
+            thread_id,
+        };
+
+        let mut parsed = Vec::new();
+        let reviews = pull_request
+            .reviews
+            .as_ref()
+            .and_then(|reviews| reviews.nodes.as_ref());
+        for review in reviews.into_iter().flatten().flatten() {
+            if let Some(ref author) = review.author {
+                parsed.push(node(
+                    &author.login,
+                    Interaction::Review,
+                    Some(ReviewNode::state_to_string(&review.state)),
+                    review.submitted_at,
+                    None,
+                ));
+            }
+        }
+
+        let threads = pull_request.review_threads.nodes.iter().flatten().flatten();
+        for thread in threads {
+            let comments = thread.comments.nodes.iter().flatten().flatten();
+            for comment in comments {
+                if let Some(ref author) = comment.author {
+                    parsed.push(node(
+                        &author.login,
+                        Interaction::ReviewComment,
+                        None,
+                        Some(comment.created_at),
+                        Some(thread.id.clone()),
+                    ));
+                }
+            }
+        }
+        parsed
+    }
+
+    /// Pulls the reviews and review comments out of ReviewView's responses.
+    pub fn parse_nodes(data: &[review_view::ResponseData]) -> Vec<ReviewNode> {
+        let mut parsed = Vec::new();
+
+        for unparsed in data.iter() {
+            match unparsed.repository {
+                Some(ref repo) => {
+                    let pull_requests = repo.pull_requests.edges.iter().flatten().flatten();
+                    for pull_request in pull_requests.filter_map(|edge| edge.node.as_ref()) {
+                        parsed.extend(ReviewNode::from_pull_request(
+                            &repo.name_with_owner,
+                            pull_request,
+                        ));
+                    }
+                }
+                None => warn!("Empty data found while parsing. Data: {:#?}", unparsed),
+            }
+        }
+        parsed
+    }
+}
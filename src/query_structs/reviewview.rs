@@ -0,0 +1,66 @@
+#![warn(clippy::all)]
+// See repoview.rs; the custom scalars must be in scope for the derive.
+use super::{
+    backoff_timer::{backoff, BackoffTimer},
+    cursor::{page_to_end, Cursor},
+    scalars::DateTime,
+};
+use crate::{error::Result as GGGResult, query_client::QueryClient};
+use graphql_client::GraphQLQuery;
+use log::info;
+use std::time::Duration;
+
+// Reviews and review threads nest two more connections under every pull request so they're only
+// queried when asked for rather than alongside the participants in RepoView.
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "queries/ghschema.graphql",
+    query_path = "queries/reviewquery.graphql",
+    response_derives = "Clone,Debug"
+)]
+pub struct ReviewView;
+
+impl Cursor<ReviewView> for ReviewView {
+    fn cursor(response: &review_view::ResponseData) -> Option<String> {
+        match response.repository {
+            Some(ref repo_data) if repo_data.pull_requests.page_info.has_next_page => repo_data
+                .pull_requests
+                .edges
+                .as_ref()
+                .and_then(|edges_vec| edges_vec.iter().last())
+                .and_then(|vec_last| vec_last.as_ref().map(|edge| edge.cursor.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffTimer<ReviewView> for ReviewView {
+    fn backoff(response: &review_view::ResponseData) -> Option<Duration> {
+        response
+            .rate_limit
+            .as_ref()
+            .and_then(|ratelimit| backoff(ratelimit.remaining, ratelimit.reset_at))
+    }
+}
+
+/// Pages through the reviews of owner/name's pull requests. Only the first nnodes reviews,
+/// review threads, and comments of each thread are fetched.
+pub async fn query_reviews_to_end(
+    client: &QueryClient,
+    owner: &str,
+    name: &str,
+    nnodes: i64,
+) -> GGGResult<Vec<review_view::ResponseData>> {
+    info!("Scraping reviews from {}/{}", owner, name);
+
+    page_to_end::<ReviewView, _>(client, |pullcursor| {
+        ReviewView::build_query(review_view::Variables {
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            nnodes,
+            pullcursor,
+        })
+    })
+    .await
+}
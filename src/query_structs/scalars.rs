@@ -0,0 +1,162 @@
+#![warn(clippy::all)]
+use crate::error::{Error, ErrorKind, Result};
+use chrono::{FixedOffset, SecondsFormat, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+use url::Url;
+
+// The GitHub GraphQL schema defines custom scalars that graphql_client expects to find in scope
+// of the query struct. Each scalar is a newtype so that it may parse itself while deserializing
+// and report a typed error if GitHub hands us something odd.
+
+/// GitHub's DateTime scalar: an ISO-8601 timestamp in UTC. Serialized as RFC 3339.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DateTime(pub chrono::DateTime<Utc>);
+
+/// GitHub's GitTimestamp scalar. Unlike DateTime the committer's UTC offset is kept.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GitTimestamp(pub chrono::DateTime<FixedOffset>);
+
+/// GitHub's GitObjectID scalar (a commit's SHA). There's nothing to check so it stays a String.
+#[allow(clippy::upper_case_acronyms)]
+pub type GitObjectID = String;
+
+/// GitHub's URI scalar.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct URI(pub Url);
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    fn from_str(timestamp: &str) -> Result<Self> {
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|parsed| DateTime(parsed.with_timezone(&Utc)))
+            .map_err(|e| {
+                Error::new(
+                    format!("Parsing DateTime ({})", timestamp),
+                    ErrorKind::Timestamp(e),
+                )
+            })
+    }
+}
+
+impl FromStr for GitTimestamp {
+    type Err = Error;
+
+    fn from_str(timestamp: &str) -> Result<Self> {
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(GitTimestamp)
+            .map_err(|e| {
+                Error::new(
+                    format!("Parsing GitTimestamp ({})", timestamp),
+                    ErrorKind::Timestamp(e),
+                )
+            })
+    }
+}
+
+impl FromStr for URI {
+    type Err = Error;
+
+    fn from_str(uri: &str) -> Result<Self> {
+        Url::parse(uri)
+            .map(URI)
+            .map_err(|e| Error::new(format!("Parsing URI ({})", uri), ErrorKind::Url(e)))
+    }
+}
+
+// Commits are placed on the same UTC timeline as pull requests.
+impl From<GitTimestamp> for DateTime {
+    fn from(timestamp: GitTimestamp) -> Self {
+        DateTime(timestamp.0.with_timezone(&Utc))
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+}
+
+impl Display for GitTimestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+}
+
+impl Display for URI {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// All three scalars are strings on the wire so they share their (de)serialization through
+// FromStr and Display.
+macro_rules! string_scalar {
+    ($scalar:ty) => {
+        impl<'de> Deserialize<'de> for $scalar {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+
+        impl Serialize for $scalar {
+            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_str(self)
+            }
+        }
+    };
+}
+
+string_scalar!(DateTime);
+string_scalar!(GitTimestamp);
+string_scalar!(URI);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_time_is_normalized_to_utc() {
+        let parsed: DateTime = "2020-05-01T12:30:00+02:00".parse().unwrap();
+        assert_eq!(parsed.to_string(), "2020-05-01T10:30:00Z");
+    }
+
+    #[test]
+    fn git_timestamp_keeps_its_offset() {
+        let parsed: GitTimestamp = "2020-05-01T12:30:00+02:00".parse().unwrap();
+        assert_eq!(parsed.to_string(), "2020-05-01T12:30:00+02:00");
+        assert_eq!(DateTime::from(parsed).to_string(), "2020-05-01T10:30:00Z");
+    }
+
+    #[test]
+    fn bad_scalars_are_rejected() {
+        assert!("2020-05-01".parse::<DateTime>().is_err());
+        assert!("yesterday".parse::<GitTimestamp>().is_err());
+        assert!("not a url".parse::<URI>().is_err());
+    }
+
+    #[test]
+    fn scalars_round_trip_through_json() {
+        let date: DateTime = serde_json::from_str("\"2020-05-01T10:30:00Z\"").unwrap();
+        assert_eq!(
+            serde_json::to_string(&date).unwrap(),
+            "\"2020-05-01T10:30:00Z\""
+        );
+        let uri: URI = serde_json::from_str("\"https://github.com/rust-lang\"").unwrap();
+        assert_eq!(uri.0.host_str(), Some("github.com"));
+        assert!(serde_json::from_str::<DateTime>("\"soon\"").is_err());
+    }
+}
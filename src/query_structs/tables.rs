@@ -0,0 +1,219 @@
+#![warn(clippy::all)]
+use super::{repoview_nodes::RepoViewNode, scalars::DateTime};
+use crate::error::{Error, ErrorKind, Result};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// File format of each normalized table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TableFormat {
+    Csv,
+    Parquet,
+}
+
+impl TableFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for TableFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(TableFormat::Csv),
+            "parquet" => Ok(TableFormat::Parquet),
+            _ => Err(Error::new(
+                format!("Unknown table format ({})", format),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+// The tables use natural keys rather than row numbers so that tables from separate runs or
+// repositories may be concatenated and joined without renumbering. Users are keyed by login,
+// repositories by owner/name, and pull requests by GitHub's node ID.
+
+#[derive(Clone, Debug, Serialize)]
+pub struct UserRow {
+    pub user_id: String,
+    pub location: Option<String>,
+    pub company: Option<String>,
+    pub unknown_organizations: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RepositoryRow {
+    pub repository_id: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PullRequestRow {
+    pub pull_request_id: String,
+    pub repository_id: String,
+    pub number: i64,
+    pub title: String,
+    pub url: Option<String>,
+    pub state: String,
+    /// None for deleted ("ghost") accounts.
+    pub author_id: Option<String>,
+    pub author_association: String,
+    pub created_at: DateTime,
+    pub merged_at: Option<DateTime>,
+    pub closed_at: Option<DateTime>,
+    pub merged_by_id: Option<String>,
+    pub additions: i64,
+    pub deletions: i64,
+    pub changed_files: i64,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct ParticipationRow {
+    pub pull_request_id: String,
+    pub user_id: String,
+    pub role: &'static str,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct UserOrganizationRow {
+    pub user_id: String,
+    pub organization: String,
+}
+
+/// RepoViewNodes split into deduplicated relational tables.
+#[derive(Clone, Debug, Default)]
+pub struct Tables {
+    pub users: Vec<UserRow>,
+    pub repositories: Vec<RepositoryRow>,
+    pub pull_requests: Vec<PullRequestRow>,
+    pub participations: Vec<ParticipationRow>,
+    pub user_organizations: Vec<UserOrganizationRow>,
+}
+
+// Nodes scraped before pull request IDs were recorded fall back on the same keys as
+// ContributorGraph.
+fn pull_request_id(node: &RepoViewNode) -> String {
+    if !node.pr_id.is_empty() {
+        node.pr_id.clone()
+    } else if node.pr_number > 0 {
+        format!("{}#{}", node.repository, node.pr_number)
+    } else {
+        format!(
+            "{}#{}@{}",
+            node.repository, node.pull_req_title, node.date_created
+        )
+    }
+}
+
+impl Tables {
+    /// Rows are sorted by their keys. A user's location and company come from the first node
+    /// that lists them.
+    pub fn from_nodes(nodes: &[RepoViewNode]) -> Self {
+        let mut users: BTreeMap<String, UserRow> = BTreeMap::new();
+        let mut repositories: BTreeSet<&str> = BTreeSet::new();
+        let mut pull_requests: BTreeMap<String, PullRequestRow> = BTreeMap::new();
+        let mut participations: BTreeSet<ParticipationRow> = BTreeSet::new();
+        let mut user_organizations: BTreeSet<UserOrganizationRow> = BTreeSet::new();
+
+        for node in nodes {
+            let user = users
+                .entry(node.participant.clone())
+                .or_insert_with(|| UserRow {
+                    user_id: node.participant.clone(),
+                    location: None,
+                    company: None,
+                    unknown_organizations: 0,
+                });
+            if user.location.is_none() {
+                user.location = node.location.clone();
+            }
+            if user.company.is_none() {
+                user.company = node.company.clone();
+            }
+            user.unknown_organizations = user.unknown_organizations.max(node.unknown_organizations);
+
+            repositories.insert(node.repository.as_str());
+
+            let pull_request_id = pull_request_id(node);
+            pull_requests
+                .entry(pull_request_id.clone())
+                .or_insert_with(|| PullRequestRow {
+                    pull_request_id: pull_request_id.clone(),
+                    repository_id: node.repository.clone(),
+                    number: node.pr_number,
+                    title: node.pull_req_title.clone(),
+                    url: node.pr_url.as_ref().map(|url| url.to_string()),
+                    state: node.state.clone(),
+                    author_id: node.pr_author.clone(),
+                    author_association: node.author_association.clone(),
+                    created_at: node.date_created,
+                    merged_at: node.merged_at,
+                    closed_at: node.closed_at,
+                    merged_by_id: node.merged_by.clone(),
+                    additions: node.additions,
+                    deletions: node.deletions,
+                    changed_files: node.changed_files,
+                });
+
+            participations.insert(ParticipationRow {
+                pull_request_id,
+                user_id: node.participant.clone(),
+                role: node.role.as_str(),
+            });
+
+            for org in node.organizations.iter() {
+                user_organizations.insert(UserOrganizationRow {
+                    user_id: node.participant.clone(),
+                    organization: org.clone(),
+                });
+            }
+        }
+
+        // Authors and mergers that never showed up as participants still need a row so that
+        // the foreign keys resolve.
+        for pull_request in pull_requests.values() {
+            let referenced = pull_request
+                .author_id
+                .iter()
+                .chain(pull_request.merged_by_id.iter());
+            for user_id in referenced {
+                users.entry(user_id.clone()).or_insert_with(|| UserRow {
+                    user_id: user_id.clone(),
+                    location: None,
+                    company: None,
+                    unknown_organizations: 0,
+                });
+            }
+        }
+
+        Tables {
+            users: users.into_values().collect(),
+            repositories: repositories
+                .into_iter()
+                .map(|repository| RepositoryRow {
+                    repository_id: repository.to_owned(),
+                })
+                .collect(),
+            pull_requests: pull_requests.into_values().collect(),
+            participations: participations.into_iter().collect(),
+            user_organizations: user_organizations.into_iter().collect(),
+        }
+    }
+
+    /// Number of rows across every table.
+    pub fn row_count(&self) -> usize {
+        self.users.len()
+            + self.repositories.len()
+            + self.pull_requests.len()
+            + self.participations.len()
+            + self.user_organizations.len()
+    }
+}
@@ -0,0 +1,238 @@
+#![warn(clippy::all)]
+use super::{
+    issueview_nodes::IssueViewNode, profileview_nodes::UserProfile, repoview_nodes::RepoViewNode,
+    scalars::URI,
+};
+use crate::error::{Error, ErrorKind, Result};
+use csv::Writer;
+use serde::Serialize;
+use std::{collections::HashSet, io::Write, str::FromStr};
+
+/// Separator used when organizations are packed into a single CSV column.
+pub const ORG_DELIMITER: &str = ";";
+
+/// CSV can't hold RepoViewNode::organizations directly so the Vector is either packed into a
+/// single column or written as a separate long format table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrganizationLayout {
+    /// Organizations are joined by ORG_DELIMITER into an "organizations" column.
+    Delimited,
+    /// Organizations are left out of the node rows and written by write_organizations_csv.
+    Long,
+}
+
+impl FromStr for OrganizationLayout {
+    type Err = Error;
+
+    fn from_str(layout: &str) -> Result<Self> {
+        match layout.to_lowercase().as_str() {
+            "delimited" => Ok(OrganizationLayout::Delimited),
+            "long" => Ok(OrganizationLayout::Long),
+            _ => Err(Error::new(
+                format!("Unknown organization layout ({})", layout),
+                ErrorKind::BadArgs,
+            )),
+        }
+    }
+}
+
+/// Writes one CSV row per RepoViewNode with a header row. Missing locations and companies are
+/// left empty.
+pub fn write_nodes_csv<W>(
+    writer: W,
+    nodes: &[RepoViewNode],
+    layout: OrganizationLayout,
+) -> Result<()>
+where
+    W: Write,
+{
+    let mut csv_writer = Writer::from_writer(writer);
+    let mut header = vec![
+        "repository",
+        "participant",
+        "role",
+        "pr_author",
+        "author_association",
+        "date_created",
+        "pull_req_title",
+        "pr_number",
+        "pr_url",
+        "pr_id",
+        "state",
+        "merged_at",
+        "closed_at",
+        "merged_by",
+        "additions",
+        "deletions",
+        "changed_files",
+        "location",
+        "company",
+    ];
+    if layout == OrganizationLayout::Delimited {
+        header.push("organizations");
+    }
+    header.push("unknown_organizations");
+    csv_writer.write_record(&header)?;
+
+    for node in nodes {
+        let mut record = vec![
+            node.repository.clone(),
+            node.participant.clone(),
+            node.role.as_str().to_owned(),
+            node.pr_author.clone().unwrap_or_default(),
+            node.author_association.clone(),
+            node.date_created.to_string(),
+            node.pull_req_title.clone(),
+            node.pr_number.to_string(),
+            node.pr_url
+                .as_ref()
+                .map_or_else(String::new, URI::to_string),
+            node.pr_id.clone(),
+            node.state.clone(),
+            node.merged_at
+                .map_or_else(String::new, |merged| merged.to_string()),
+            node.closed_at
+                .map_or_else(String::new, |closed| closed.to_string()),
+            node.merged_by.clone().unwrap_or_default(),
+            node.additions.to_string(),
+            node.deletions.to_string(),
+            node.changed_files.to_string(),
+            node.location.clone().unwrap_or_default(),
+            node.company.clone().unwrap_or_default(),
+        ];
+        if layout == OrganizationLayout::Delimited {
+            record.push(node.organizations.join(ORG_DELIMITER));
+        }
+        record.push(node.unknown_organizations.to_string());
+        csv_writer.write_record(&record)?;
+    }
+    Ok(csv_writer.flush()?)
+}
+
+/// Writes one CSV row per IssueViewNode with a header row. Labels and organizations are both
+/// joined by ORG_DELIMITER.
+pub fn write_issues_csv<W>(writer: W, nodes: &[IssueViewNode]) -> Result<()>
+where
+    W: Write,
+{
+    let mut csv_writer = Writer::from_writer(writer);
+    csv_writer.write_record([
+        "repository",
+        "participant",
+        "role",
+        "issue_author",
+        "author_association",
+        "date_created",
+        "closed_at",
+        "issue_title",
+        "issue_number",
+        "issue_url",
+        "issue_id",
+        "state",
+        "labels",
+        "location",
+        "company",
+        "organizations",
+        "unknown_organizations",
+    ])?;
+
+    for node in nodes {
+        csv_writer.write_record(&[
+            node.repository.clone(),
+            node.participant.clone(),
+            node.role.as_str().to_owned(),
+            node.issue_author.clone().unwrap_or_default(),
+            node.author_association.clone(),
+            node.date_created.to_string(),
+            node.closed_at
+                .map_or_else(String::new, |closed| closed.to_string()),
+            node.issue_title.clone(),
+            node.issue_number.to_string(),
+            node.issue_url.to_string(),
+            node.issue_id.clone(),
+            node.state.clone(),
+            node.labels.join(ORG_DELIMITER),
+            node.location.clone().unwrap_or_default(),
+            node.company.clone().unwrap_or_default(),
+            node.organizations.join(ORG_DELIMITER),
+            node.unknown_organizations.to_string(),
+        ])?;
+    }
+    Ok(csv_writer.flush()?)
+}
+
+/// Writes one CSV row per UserProfile. Organizations are always delimited since there's only one
+/// row per user.
+pub fn write_profiles_csv<W>(writer: W, profiles: &[UserProfile]) -> Result<()>
+where
+    W: Write,
+{
+    let mut csv_writer = Writer::from_writer(writer);
+    csv_writer.write_record([
+        "login",
+        "id",
+        "name",
+        "bio",
+        "location",
+        "company",
+        "organizations",
+        "unknown_organizations",
+        "created_at",
+        "followers",
+        "twitter",
+        "website",
+    ])?;
+
+    for profile in profiles {
+        csv_writer.write_record(&[
+            profile.login.clone(),
+            profile.id.clone(),
+            profile.name.clone().unwrap_or_default(),
+            profile.bio.clone().unwrap_or_default(),
+            profile.location.clone().unwrap_or_default(),
+            profile.company.clone().unwrap_or_default(),
+            profile.organizations.join(ORG_DELIMITER),
+            profile.unknown_organizations.to_string(),
+            profile.created_at.to_string(),
+            profile.followers.to_string(),
+            profile.twitter.clone().unwrap_or_default(),
+            profile.website.clone().unwrap_or_default(),
+        ])?;
+    }
+    Ok(csv_writer.flush()?)
+}
+
+/// Writes the long format user to organization table that accompanies
+/// OrganizationLayout::Long. Users appear once per organization no matter how many pull requests
+/// they participated in.
+pub fn write_organizations_csv<W>(writer: W, nodes: &[RepoViewNode]) -> Result<()>
+where
+    W: Write,
+{
+    let mut csv_writer = Writer::from_writer(writer);
+    csv_writer.write_record(["user", "organization"])?;
+
+    let mut seen: HashSet<(&str, &str)> = HashSet::new();
+    for node in nodes {
+        for org in node.organizations.iter() {
+            if seen.insert((node.participant.as_str(), org.as_str())) {
+                csv_writer.write_record([&node.participant, org])?;
+            }
+        }
+    }
+    Ok(csv_writer.flush()?)
+}
+
+/// Writes flat records (structs without nested containers) as CSV with a header row taken from
+/// the field names.
+pub fn write_records_csv<I, W>(writer: W, records: &[I]) -> Result<()>
+where
+    I: Serialize,
+    W: Write,
+{
+    let mut csv_writer = Writer::from_writer(writer);
+    for record in records {
+        csv_writer.serialize(record)?;
+    }
+    Ok(csv_writer.flush()?)
+}
@@ -1,15 +1,30 @@
 use crate::error::Result;
 use serde::Serialize;
-use serde_json::to_writer_pretty;
+use serde_json::{to_writer, to_writer_pretty};
 use std::io::Write;
 #[warn(clippy::all)]
 
 // Okay...so there's no point for this function anymore since Serde is amazing and deserializes the
 // Vector properly, I think.
-pub fn write_nodes<I, W>(writer: W, nodes: &Vec<I>) -> Result<()>
+pub fn write_nodes<I, W>(mut writer: W, nodes: &[I]) -> Result<()>
 where
     I: Serialize,
     W: Write,
 {
-    Ok(to_writer_pretty(writer, nodes)?)
+    to_writer_pretty(&mut writer, nodes)?;
+    Ok(writer.flush()?)
+}
+
+/// Writes each node as compact JSON on its own line (JSON Lines/NDJSON). Unlike write_nodes the
+/// output may be streamed or appended to by later runs.
+pub fn write_nodes_jsonl<I, W>(mut writer: W, nodes: &[I]) -> Result<()>
+where
+    I: Serialize,
+    W: Write,
+{
+    for node in nodes {
+        to_writer(&mut writer, node)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(writer.flush()?)
 }
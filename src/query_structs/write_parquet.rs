@@ -0,0 +1,375 @@
+#![warn(clippy::all)]
+use super::{repoview_nodes::RepoViewNode, scalars::DateTime, tables::Tables};
+use crate::error::Result;
+use arrow::{
+    array::{ArrayRef, Int64Array, ListBuilder, StringArray, StringBuilder, TimestampSecondArray},
+    record_batch::RecordBatch,
+};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use std::{io::Write, sync::Arc};
+
+// Column builders. Timestamps are stored as seconds in UTC since that's GitHub's precision.
+
+fn strings<'a, I>(values: I) -> ArrayRef
+where
+    I: Iterator<Item = &'a str>,
+{
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+fn optional_strings<'a, I>(values: I) -> ArrayRef
+where
+    I: Iterator<Item = Option<&'a str>>,
+{
+    Arc::new(values.collect::<StringArray>())
+}
+
+fn integers<I>(values: I) -> ArrayRef
+where
+    I: Iterator<Item = i64>,
+{
+    Arc::new(Int64Array::from_iter_values(values))
+}
+
+fn timestamps<I>(values: I) -> ArrayRef
+where
+    I: Iterator<Item = Option<DateTime>>,
+{
+    Arc::new(
+        values
+            .map(|value| value.map(|timestamp| timestamp.0.timestamp()))
+            .collect::<TimestampSecondArray>()
+            .with_timezone("UTC"),
+    )
+}
+
+// Writes a single record batch as a Snappy compressed Parquet file.
+fn write_batch<W>(writer: W, columns: Vec<(&str, ArrayRef, bool)>) -> Result<()>
+where
+    W: Write + Send,
+{
+    let batch = RecordBatch::try_from_iter_with_nullable(columns)?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut parquet = ArrowWriter::try_new(writer, batch.schema(), Some(properties))?;
+    parquet.write(&batch)?;
+    parquet.close()?;
+    Ok(())
+}
+
+/// Writes RepoViewNodes as Parquet with the organizations stored as a list column.
+pub fn write_nodes_parquet<W>(writer: W, nodes: &[RepoViewNode]) -> Result<()>
+where
+    W: Write + Send,
+{
+    let mut organizations = ListBuilder::new(StringBuilder::new());
+    for node in nodes {
+        for org in node.organizations.iter() {
+            organizations.values().append_value(org);
+        }
+        organizations.append(true);
+    }
+
+    write_batch(
+        writer,
+        vec![
+            (
+                "repository",
+                strings(nodes.iter().map(|node| node.repository.as_str())),
+                false,
+            ),
+            (
+                "participant",
+                strings(nodes.iter().map(|node| node.participant.as_str())),
+                false,
+            ),
+            (
+                "role",
+                strings(nodes.iter().map(|node| node.role.as_str())),
+                false,
+            ),
+            (
+                "pr_author",
+                optional_strings(nodes.iter().map(|node| node.pr_author.as_deref())),
+                true,
+            ),
+            (
+                "author_association",
+                strings(nodes.iter().map(|node| node.author_association.as_str())),
+                false,
+            ),
+            (
+                "date_created",
+                timestamps(nodes.iter().map(|node| Some(node.date_created))),
+                false,
+            ),
+            (
+                "pull_req_title",
+                strings(nodes.iter().map(|node| node.pull_req_title.as_str())),
+                false,
+            ),
+            (
+                "pr_number",
+                integers(nodes.iter().map(|node| node.pr_number)),
+                false,
+            ),
+            (
+                "pr_url",
+                Arc::new(
+                    nodes
+                        .iter()
+                        .map(|node| node.pr_url.as_ref().map(|url| url.to_string()))
+                        .collect::<StringArray>(),
+                ),
+                true,
+            ),
+            (
+                "pr_id",
+                strings(nodes.iter().map(|node| node.pr_id.as_str())),
+                false,
+            ),
+            (
+                "state",
+                strings(nodes.iter().map(|node| node.state.as_str())),
+                false,
+            ),
+            (
+                "merged_at",
+                timestamps(nodes.iter().map(|node| node.merged_at)),
+                true,
+            ),
+            (
+                "closed_at",
+                timestamps(nodes.iter().map(|node| node.closed_at)),
+                true,
+            ),
+            (
+                "merged_by",
+                optional_strings(nodes.iter().map(|node| node.merged_by.as_deref())),
+                true,
+            ),
+            (
+                "additions",
+                integers(nodes.iter().map(|node| node.additions)),
+                false,
+            ),
+            (
+                "deletions",
+                integers(nodes.iter().map(|node| node.deletions)),
+                false,
+            ),
+            (
+                "changed_files",
+                integers(nodes.iter().map(|node| node.changed_files)),
+                false,
+            ),
+            (
+                "location",
+                optional_strings(nodes.iter().map(|node| node.location.as_deref())),
+                true,
+            ),
+            (
+                "company",
+                optional_strings(nodes.iter().map(|node| node.company.as_deref())),
+                true,
+            ),
+            ("organizations", Arc::new(organizations.finish()), false),
+            (
+                "unknown_organizations",
+                integers(nodes.iter().map(|node| node.unknown_organizations as i64)),
+                false,
+            ),
+        ],
+    )
+}
+
+/// Writes each normalized table as its own Parquet file. open is called with the table's name
+/// (users, repositories, pull_requests, participations, or user_organizations).
+pub fn write_tables_parquet<F, W>(tables: &Tables, mut open: F) -> Result<()>
+where
+    F: FnMut(&str) -> Result<W>,
+    W: Write + Send,
+{
+    let users = &tables.users;
+    write_batch(
+        open("users")?,
+        vec![
+            (
+                "user_id",
+                strings(users.iter().map(|user| user.user_id.as_str())),
+                false,
+            ),
+            (
+                "location",
+                optional_strings(users.iter().map(|user| user.location.as_deref())),
+                true,
+            ),
+            (
+                "company",
+                optional_strings(users.iter().map(|user| user.company.as_deref())),
+                true,
+            ),
+            (
+                "unknown_organizations",
+                integers(users.iter().map(|user| user.unknown_organizations as i64)),
+                false,
+            ),
+        ],
+    )?;
+
+    write_batch(
+        open("repositories")?,
+        vec![(
+            "repository_id",
+            strings(
+                tables
+                    .repositories
+                    .iter()
+                    .map(|repository| repository.repository_id.as_str()),
+            ),
+            false,
+        )],
+    )?;
+
+    let pull_requests = &tables.pull_requests;
+    write_batch(
+        open("pull_requests")?,
+        vec![
+            (
+                "pull_request_id",
+                strings(pull_requests.iter().map(|pr| pr.pull_request_id.as_str())),
+                false,
+            ),
+            (
+                "repository_id",
+                strings(pull_requests.iter().map(|pr| pr.repository_id.as_str())),
+                false,
+            ),
+            (
+                "number",
+                integers(pull_requests.iter().map(|pr| pr.number)),
+                false,
+            ),
+            (
+                "title",
+                strings(pull_requests.iter().map(|pr| pr.title.as_str())),
+                false,
+            ),
+            (
+                "url",
+                optional_strings(pull_requests.iter().map(|pr| pr.url.as_deref())),
+                true,
+            ),
+            (
+                "state",
+                strings(pull_requests.iter().map(|pr| pr.state.as_str())),
+                false,
+            ),
+            (
+                "author_id",
+                optional_strings(pull_requests.iter().map(|pr| pr.author_id.as_deref())),
+                true,
+            ),
+            (
+                "author_association",
+                strings(
+                    pull_requests
+                        .iter()
+                        .map(|pr| pr.author_association.as_str()),
+                ),
+                false,
+            ),
+            (
+                "created_at",
+                timestamps(pull_requests.iter().map(|pr| Some(pr.created_at))),
+                false,
+            ),
+            (
+                "merged_at",
+                timestamps(pull_requests.iter().map(|pr| pr.merged_at)),
+                true,
+            ),
+            (
+                "closed_at",
+                timestamps(pull_requests.iter().map(|pr| pr.closed_at)),
+                true,
+            ),
+            (
+                "merged_by_id",
+                optional_strings(pull_requests.iter().map(|pr| pr.merged_by_id.as_deref())),
+                true,
+            ),
+            (
+                "additions",
+                integers(pull_requests.iter().map(|pr| pr.additions)),
+                false,
+            ),
+            (
+                "deletions",
+                integers(pull_requests.iter().map(|pr| pr.deletions)),
+                false,
+            ),
+            (
+                "changed_files",
+                integers(pull_requests.iter().map(|pr| pr.changed_files)),
+                false,
+            ),
+        ],
+    )?;
+
+    let participations = &tables.participations;
+    write_batch(
+        open("participations")?,
+        vec![
+            (
+                "pull_request_id",
+                strings(
+                    participations
+                        .iter()
+                        .map(|participation| participation.pull_request_id.as_str()),
+                ),
+                false,
+            ),
+            (
+                "user_id",
+                strings(
+                    participations
+                        .iter()
+                        .map(|participation| participation.user_id.as_str()),
+                ),
+                false,
+            ),
+            (
+                "role",
+                strings(
+                    participations
+                        .iter()
+                        .map(|participation| participation.role),
+                ),
+                false,
+            ),
+        ],
+    )?;
+
+    let user_organizations = &tables.user_organizations;
+    write_batch(
+        open("user_organizations")?,
+        vec![
+            (
+                "user_id",
+                strings(user_organizations.iter().map(|row| row.user_id.as_str())),
+                false,
+            ),
+            (
+                "organization",
+                strings(
+                    user_organizations
+                        .iter()
+                        .map(|row| row.organization.as_str()),
+                ),
+                false,
+            ),
+        ],
+    )
+}
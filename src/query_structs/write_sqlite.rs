@@ -0,0 +1,192 @@
+#![warn(clippy::all)]
+use super::tables::Tables;
+use crate::error::{Error, ErrorKind, Result};
+use log::info;
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+// Each entry migrates the schema from the previous version. The database's user_version pragma
+// holds the number of migrations already applied so new tables or columns are added by
+// appending a migration rather than editing an old one.
+const MIGRATIONS: [&str; 1] = [r#"
+CREATE TABLE users (
+    user_id TEXT PRIMARY KEY,
+    location TEXT,
+    company TEXT,
+    unknown_organizations INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE repositories (
+    repository_id TEXT PRIMARY KEY
+);
+CREATE TABLE organizations (
+    organization_id TEXT PRIMARY KEY
+);
+CREATE TABLE pull_requests (
+    pull_request_id TEXT PRIMARY KEY,
+    repository_id TEXT NOT NULL REFERENCES repositories (repository_id),
+    number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT,
+    state TEXT NOT NULL,
+    author_id TEXT REFERENCES users (user_id),
+    author_association TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    merged_at TEXT,
+    closed_at TEXT,
+    merged_by_id TEXT REFERENCES users (user_id),
+    additions INTEGER NOT NULL,
+    deletions INTEGER NOT NULL,
+    changed_files INTEGER NOT NULL
+);
+CREATE TABLE participations (
+    pull_request_id TEXT NOT NULL REFERENCES pull_requests (pull_request_id),
+    user_id TEXT NOT NULL REFERENCES users (user_id),
+    role TEXT NOT NULL,
+    PRIMARY KEY (pull_request_id, user_id)
+);
+CREATE TABLE user_organizations (
+    user_id TEXT NOT NULL REFERENCES users (user_id),
+    organization_id TEXT NOT NULL REFERENCES organizations (organization_id),
+    PRIMARY KEY (user_id, organization_id)
+);
+CREATE INDEX pull_requests_repository ON pull_requests (repository_id);
+CREATE INDEX participations_user ON participations (user_id);
+"#];
+
+/// Opens (or creates) the database at path and brings its schema up to date.
+pub fn open_database<P: AsRef<Path>>(path: P) -> Result<Connection> {
+    let mut connection = Connection::open(path.as_ref())?;
+    let version: i64 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version as usize > MIGRATIONS.len() {
+        return Err(Error::new(
+            format!(
+                "{} has schema version {} but only {} is supported",
+                path.as_ref().display(),
+                version,
+                MIGRATIONS.len()
+            ),
+            ErrorKind::BadArgs,
+        ));
+    }
+
+    let transaction = connection.transaction()?;
+    for (applied, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!("Migrating database schema to version {}", applied + 1);
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", &(applied as i64 + 1))?;
+    }
+    transaction.commit()?;
+    Ok(connection)
+}
+
+// Later runs fill in missing user details without erasing ones an earlier run found.
+fn upsert_users(transaction: &Transaction, tables: &Tables) -> Result<()> {
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO users (user_id, location, company, unknown_organizations)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (user_id) DO UPDATE SET
+             location = COALESCE(excluded.location, location),
+             company = COALESCE(excluded.company, company),
+             unknown_organizations = excluded.unknown_organizations",
+    )?;
+    for user in tables.users.iter() {
+        statement.execute(params![
+            user.user_id,
+            user.location,
+            user.company,
+            user.unknown_organizations as i64
+        ])?;
+    }
+    Ok(())
+}
+
+// Pull requests change state between runs so the latest values win, except that older nodes
+// without a URL or user never erase one that is already known.
+fn upsert_pull_requests(transaction: &Transaction, tables: &Tables) -> Result<()> {
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO pull_requests (pull_request_id, repository_id, number, title, url, state,
+             author_id, author_association, created_at, merged_at, closed_at, merged_by_id,
+             additions, deletions, changed_files)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+         ON CONFLICT (pull_request_id) DO UPDATE SET
+             title = excluded.title,
+             url = COALESCE(excluded.url, url),
+             state = excluded.state,
+             author_id = COALESCE(excluded.author_id, author_id),
+             author_association = excluded.author_association,
+             merged_at = excluded.merged_at,
+             closed_at = excluded.closed_at,
+             merged_by_id = COALESCE(excluded.merged_by_id, merged_by_id),
+             additions = excluded.additions,
+             deletions = excluded.deletions,
+             changed_files = excluded.changed_files",
+    )?;
+    for pull_request in tables.pull_requests.iter() {
+        statement.execute(params![
+            pull_request.pull_request_id,
+            pull_request.repository_id,
+            pull_request.number,
+            pull_request.title,
+            pull_request.url,
+            pull_request.state,
+            pull_request.author_id,
+            pull_request.author_association,
+            pull_request.created_at.to_string(),
+            pull_request.merged_at.map(|merged| merged.to_string()),
+            pull_request.closed_at.map(|closed| closed.to_string()),
+            pull_request.merged_by_id,
+            pull_request.additions,
+            pull_request.deletions,
+            pull_request.changed_files
+        ])?;
+    }
+    Ok(())
+}
+
+/// Merges normalized tables into the database at path within a single transaction. Rows that
+/// already exist are updated rather than duplicated so repeated and incremental runs may share a
+/// database.
+pub fn write_sqlite<P: AsRef<Path>>(path: P, tables: &Tables) -> Result<()> {
+    let mut connection = open_database(path)?;
+    let transaction = connection.transaction()?;
+
+    upsert_users(&transaction, tables)?;
+    {
+        let mut statement = transaction
+            .prepare_cached("INSERT OR IGNORE INTO repositories (repository_id) VALUES (?1)")?;
+        for repository in tables.repositories.iter() {
+            statement.execute(params![repository.repository_id])?;
+        }
+    }
+    upsert_pull_requests(&transaction, tables)?;
+    {
+        let mut statement = transaction.prepare_cached(
+            "INSERT INTO participations (pull_request_id, user_id, role) VALUES (?1, ?2, ?3)
+             ON CONFLICT (pull_request_id, user_id) DO UPDATE SET role = excluded.role",
+        )?;
+        for participation in tables.participations.iter() {
+            statement.execute(params![
+                participation.pull_request_id,
+                participation.user_id,
+                participation.role
+            ])?;
+        }
+    }
+    {
+        let mut organization = transaction
+            .prepare_cached("INSERT OR IGNORE INTO organizations (organization_id) VALUES (?1)")?;
+        let mut membership = transaction.prepare_cached(
+            "INSERT OR IGNORE INTO user_organizations (user_id, organization_id) VALUES (?1, ?2)",
+        )?;
+        for user_organization in tables.user_organizations.iter() {
+            organization.execute(params![user_organization.organization])?;
+            membership.execute(params![
+                user_organization.user_id,
+                user_organization.organization
+            ])?;
+        }
+    }
+
+    transaction.commit()?;
+    Ok(())
+}